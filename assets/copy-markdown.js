@@ -0,0 +1,33 @@
+(function() {
+  'use strict';
+
+  const button = document.getElementById('copy-markdown');
+  if (!button) return;
+
+  // The Markdown mirror lives next to the page: a/b.html -> a/b.md
+  function markdownUrl() {
+    return window.location.pathname.replace(/\.html$/, '') + '.md';
+  }
+
+  function showFeedback(title) {
+    const original = button.title;
+    button.title = title;
+    button.classList.add('copied');
+    setTimeout(() => {
+      button.title = original;
+      button.classList.remove('copied');
+    }, 2000);
+  }
+
+  button.addEventListener('click', async () => {
+    try {
+      const response = await fetch(markdownUrl());
+      if (!response.ok) throw new Error('HTTP ' + response.status);
+      await navigator.clipboard.writeText(await response.text());
+      showFeedback('Copied!');
+    } catch (error) {
+      console.error('Failed to copy page as Markdown:', error);
+      showFeedback('Failed to copy');
+    }
+  });
+})();
@@ -0,0 +1,59 @@
+(function() {
+  'use strict';
+
+  const MAX_SUGGESTIONS = 5;
+
+  // Levenshtein distance between two strings
+  function editDistance(a, b) {
+    const prev = new Array(b.length + 1);
+    for (let j = 0; j <= b.length; j++) prev[j] = j;
+
+    for (let i = 1; i <= a.length; i++) {
+      let diag = prev[0];
+      prev[0] = i;
+      for (let j = 1; j <= b.length; j++) {
+        const tmp = prev[j];
+        const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+        prev[j] = Math.min(prev[j] + 1, prev[j - 1] + 1, diag + cost);
+        diag = tmp;
+      }
+    }
+    return prev[b.length];
+  }
+
+  // Requested path relative to base_path (e.g. "/book/a/b.html" -> "a/b.html")
+  function requestedPath(basePath) {
+    let path = decodeURIComponent(window.location.pathname);
+    if (basePath && path.startsWith(basePath + '/')) {
+      path = path.substring(basePath.length);
+    }
+    return path.replace(/^\/+/, '');
+  }
+
+  function escapeHtml(str) {
+    return str
+      .replace(/&/g, '&amp;')
+      .replace(/</g, '&lt;')
+      .replace(/>/g, '&gt;')
+      .replace(/"/g, '&quot;');
+  }
+
+  document.addEventListener('DOMContentLoaded', function() {
+    const data = window.UNIBOOK_NOT_FOUND;
+    const list = document.getElementById('not-found-suggestions');
+    if (!data || !list) return;
+
+    const requested = requestedPath(data.base_path).toLowerCase();
+    const ranked = data.pages
+      .map(page => ({
+        page: page,
+        distance: editDistance(requested, page.path.toLowerCase()),
+      }))
+      .sort((a, b) => a.distance - b.distance)
+      .slice(0, MAX_SUGGESTIONS);
+
+    list.innerHTML = ranked.map(item => `
+      <li><a href="${escapeHtml(data.base_path + '/' + item.page.path)}">${escapeHtml(item.page.title)}</a></li>
+    `).join('');
+  });
+})();
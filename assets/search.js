@@ -1,23 +1,233 @@
 (function() {
   'use strict';
 
-  let searchIndex = null;
+  // Manifest: normalization, and the first term / entry of each shard
+  let manifest = null;
+  let manifestPromise = null;
+  // Shard number -> promise of { terms, sorted } / of an entries array
+  const termShards = new Map();
+  const entryShards = new Map();
   let selectedIndex = -1;
+  let searchGeneration = 0;
 
-  // Load search index
-  async function loadSearchIndex() {
-    if (searchIndex) return searchIndex;
+  const MAX_RESULTS = 50;
+
+  // Half-width katakana U+FF66..U+FF9D, in order
+  const HALF_WIDTH_KATAKANA =
+    'ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン';
+  // Same ranges as is_cjk in src/book.rs
+  const CJK = /[\u3005\u3040-\u30FF\u3400-\u4DBF\u4E00-\u9FFF\uAC00-\uD7AF\uF900-\uFAFF\uFF66-\uFF9F\u{20000}-\u{2FA1F}]/u;
+  const WORD = /[\p{Alphabetic}\p{N}_]/u;
+
+  // Load the search index manifest; shards are fetched as queries need them
+  function loadSearchIndex() {
+    if (!manifestPromise) manifestPromise = fetchManifest();
+    return manifestPromise;
+  }
+
+  async function fetchManifest() {
+    // Index embedded in the page (single-file export), used as a single shard
+    const embedded = window.UNIBOOK_SEARCH_INDEX;
+    if (embedded) {
+      termShards.set(0, Promise.resolve(prepareTerms(embedded.terms)));
+      entryShards.set(0, Promise.resolve(embedded.entries));
+      manifest = { normalization: embedded.normalization, terms: [''], entries: [0] };
+      return manifest;
+    }
 
     try {
-      const response = await fetch('search-index.json');
-      searchIndex = await response.json();
-      return searchIndex;
+      manifest = await fetchJson(indexUrl());
+      return manifest;
     } catch (error) {
       console.error('Failed to load search index:', error);
+      manifestPromise = null;
       return null;
     }
   }
 
+  // base_path-absolute manifest URL from the page's <meta>
+  function indexUrl() {
+    const meta = document.querySelector('meta[name="unibook-search-index"]');
+    return meta ? meta.content : 'search-index.json';
+  }
+
+  async function fetchJson(url) {
+    const response = await fetch(url);
+    if (!response.ok) throw new Error(url + ': ' + response.status);
+    return response.json();
+  }
+
+  function loadShard(cache, kind, number) {
+    if (!cache.has(number)) {
+      const url = indexUrl();
+      const dir = url.substring(0, url.lastIndexOf('/') + 1);
+      const shard = fetchJson(dir + 'search-index/' + kind + '-' + number + '.json');
+      cache.set(number, kind === 'terms' ? shard.then(prepareTerms) : shard);
+    }
+    return cache.get(number);
+  }
+
+  function prepareTerms(terms) {
+    // Sorted for prefix lookups of the last query token
+    return { terms: terms, sorted: Object.keys(terms).sort(compareTerms) };
+  }
+
+  // Order of the terms in the Rust BTreeMap that cuts the shards: by code
+  // point, where JS string comparison goes by UTF-16 code unit
+  function compareTerms(a, b) {
+    let i = 0;
+    while (i < a.length && i < b.length) {
+      const x = a.codePointAt(i);
+      const y = b.codePointAt(i);
+      if (x !== y) return x - y;
+      i += x > 0xFFFF ? 2 : 1;
+    }
+    return a.length - b.length;
+  }
+
+  // Index of the last element of sorted `starts` that is <= value (at least 0)
+  function lastAtMost(starts, value, compare) {
+    let low = 0;
+    let high = starts.length;
+    while (low < high) {
+      const mid = (low + high) >> 1;
+      if (compare(starts[mid], value) <= 0) low = mid + 1;
+      else high = mid;
+    }
+    return Math.max(0, low - 1);
+  }
+
+  // Same normalization as src/search_tokenizer.rs
+  function normalize(text, options) {
+    const folded = [];
+    for (const ch of text) {
+      const c = options.width ? foldWidth(ch) : ch;
+      // Half-width (semi-)voiced sound marks combine with the previous kana
+      if (options.width && (c === '\uFF9E' || c === '\uFF9F')) {
+        const voiced = folded.length > 0 ? voice(folded[folded.length - 1], c) : null;
+        if (voiced) folded[folded.length - 1] = voiced;
+        continue;
+      }
+      folded.push(c);
+    }
+    return folded.map((c) => (options.kana ? katakanaToHiragana(c) : c)).join('').toLowerCase();
+  }
+
+  function foldWidth(c) {
+    const code = c.codePointAt(0);
+    if (code >= 0xFF01 && code <= 0xFF5E) return String.fromCodePoint(code - 0xFEE0);
+    if (code === 0x3000) return ' ';
+    if (code >= 0xFF66 && code <= 0xFF9D) return HALF_WIDTH_KATAKANA[code - 0xFF66];
+    return c;
+  }
+
+  function voice(base, mark) {
+    const offset = mark === '\uFF9E' ? 1 : 2;
+    if (base === 'ウ') return offset === 1 ? 'ヴ' : null;
+    if ('カキクケコサシスセソタチツテト'.includes(base)) {
+      return offset === 1 ? String.fromCodePoint(base.codePointAt(0) + 1) : null;
+    }
+    if ('ハヒフヘホ'.includes(base)) return String.fromCodePoint(base.codePointAt(0) + offset);
+    return null;
+  }
+
+  function katakanaToHiragana(c) {
+    const code = c.codePointAt(0);
+    return code >= 0x30A1 && code <= 0x30F6 ? String.fromCodePoint(code - 0x60) : c;
+  }
+
+  // Latin words, and character bigrams of CJK runs (same as the index)
+  function tokenize(text, options) {
+    const tokens = [];
+    let word = '';
+    let cjk = [];
+    const flushBigrams = () => {
+      if (cjk.length === 1) tokens.push(cjk[0]);
+      for (let i = 0; i + 1 < cjk.length; i++) tokens.push(cjk[i] + cjk[i + 1]);
+      cjk = [];
+    };
+    for (const c of normalize(text, options) + ' ') {
+      if (CJK.test(c)) {
+        if (word) tokens.push(word);
+        word = '';
+        cjk.push(c);
+      } else {
+        flushBigrams();
+        if (WORD.test(c)) {
+          word += c;
+        } else {
+          if (word) tokens.push(word);
+          word = '';
+        }
+      }
+    }
+    return tokens;
+  }
+
+  // Entry -> weight for a term; the last query token also matches as a prefix
+  async function lookup(token, prefix) {
+    // Terms starting with the token lie in its shard and in the following
+    // shards whose first term also starts with it
+    const first = lastAtMost(manifest.terms, token, compareTerms);
+    let last = first;
+    while (prefix && last + 1 < manifest.terms.length && manifest.terms[last + 1].startsWith(token)) {
+      last++;
+    }
+    const shards = [];
+    for (let number = first; number <= last; number++) {
+      shards.push(loadShard(termShards, 'terms', number));
+    }
+
+    const weights = new Map();
+    const add = (postings) => {
+      for (const [entry, weight] of postings) {
+        weights.set(entry, Math.max(weights.get(entry) || 0, weight));
+      }
+    };
+    for (const shard of await Promise.all(shards)) {
+      if (!prefix) {
+        if (Object.prototype.hasOwnProperty.call(shard.terms, token)) add(shard.terms[token]);
+        continue;
+      }
+      for (let i = lastAtMost(shard.sorted, token, compareTerms); i < shard.sorted.length; i++) {
+        const term = shard.sorted[i];
+        if (term.startsWith(token)) add(shard.terms[term]);
+        else if (compareTerms(term, token) > 0) break;
+      }
+    }
+    return weights;
+  }
+
+  // Entries containing every query token, by summed BM25 weight
+  async function rank(query) {
+    const tokens = [...new Set(tokenize(query, manifest.normalization))];
+    if (tokens.length === 0) return [];
+
+    const lookups = await Promise.all(
+      tokens.map((token, i) => lookup(token, i === tokens.length - 1)));
+    let scores = lookups[0];
+    for (const weights of lookups.slice(1)) {
+      const next = new Map();
+      for (const [entry, weight] of weights) {
+        if (scores.has(entry)) next.set(entry, scores.get(entry) + weight);
+      }
+      scores = next;
+    }
+
+    const top = [...scores.entries()]
+      // Ties in entry order, as in `unibook search`
+      .sort((a, b) => b[1] - a[1] || a[0] - b[0])
+      .slice(0, MAX_RESULTS)
+      .map(([entry]) => entry);
+    return Promise.all(top.map(loadEntry));
+  }
+
+  async function loadEntry(entry) {
+    const number = lastAtMost(manifest.entries, entry, (a, b) => a - b);
+    const shard = await loadShard(entryShards, 'entries', number);
+    return shard[entry - manifest.entries[number]];
+  }
+
   // Open search modal
   function openSearch() {
     const modal = document.getElementById('search-modal');
@@ -46,46 +256,75 @@
   }
 
   // Perform search
-  function performSearch(query) {
+  async function performSearch(query) {
+    // Results of an older query may arrive after those of a newer one
+    const generation = ++searchGeneration;
     if (!query || query.length < 2) {
       showHint();
       return;
     }
 
-    if (!searchIndex) {
+    if (!manifest) {
       const results = document.getElementById('search-results');
       results.innerHTML = '<div class="search-hint">Loading search index...</div>';
-      return;
+      if (!(await loadSearchIndex())) return;
     }
 
-    const lowerQuery = query.toLowerCase();
-    const results = [];
+    let entries;
+    try {
+      entries = await rank(query);
+    } catch (error) {
+      console.error('Failed to load search index:', error);
+      return;
+    }
+    if (generation !== searchGeneration) return;
+
+    const words = query.toLowerCase().split(/\s+/).filter((word) => word);
+    const results = entries.map((entry) => {
+      // Find context snippet (from the code blocks when only they match)
+      const contentMatch = words.some((word) => entry.content.toLowerCase().includes(word));
+      const codeMatch = words.some((word) => entry.code.toLowerCase().includes(word));
+      const text = !contentMatch && codeMatch ? entry.code : entry.content;
+      return {
+        page: entry.title,
+        section: entry.section,
+        url: entry.url,
+        preview: extractPreview(text, words),
+      };
+    });
 
-    for (const page of searchIndex.pages) {
-      const titleMatch = page.title.toLowerCase().includes(lowerQuery);
-      const contentMatch = page.content.toLowerCase().includes(lowerQuery);
+    displayResults(groupByPage(results), words);
+  }
 
-      if (titleMatch || contentMatch) {
-        // Find context snippet
-        const preview = extractPreview(page.content, lowerQuery);
-        results.push({
-          title: page.title,
-          url: page.url,
-          preview: preview,
-        });
+  // Group section hits under their page, in order of the first hit
+  function groupByPage(results) {
+    const groups = [];
+    const byPage = new Map();
+    for (const result of results) {
+      const pageUrl = result.url.split('#')[0];
+      let group = byPage.get(pageUrl);
+      if (!group) {
+        group = { title: result.page, results: [] };
+        byPage.set(pageUrl, group);
+        groups.push(group);
       }
+      group.results.push(result);
     }
-
-    displayResults(results, query);
+    return groups;
   }
 
-  // Extract preview snippet around search term
-  function extractPreview(content, query) {
-    const index = content.toLowerCase().indexOf(query.toLowerCase());
-    if (index === -1) return content.substring(0, 100) + '...';
+  // Extract preview snippet around the first query word found
+  function extractPreview(content, words) {
+    const lower = content.toLowerCase();
+    const found = words
+      .map((word) => [lower.indexOf(word), word.length])
+      .filter(([index]) => index !== -1)
+      .sort((a, b) => a[0] - b[0]);
+    if (found.length === 0) return content.substring(0, 100) + '...';
 
+    const [index, length] = found[0];
     const start = Math.max(0, index - 40);
-    const end = Math.min(content.length, index + query.length + 60);
+    const end = Math.min(content.length, index + length + 60);
 
     let preview = content.substring(start, end);
     if (start > 0) preview = '...' + preview;
@@ -94,9 +333,10 @@
     return preview;
   }
 
-  // Highlight search terms in text
-  function highlightText(text, query) {
-    const regex = new RegExp('(' + escapeRegex(query) + ')', 'gi');
+  // Highlight query words in text
+  function highlightText(text, words) {
+    if (words.length === 0) return text;
+    const regex = new RegExp('(' + words.map(escapeRegex).join('|') + ')', 'gi');
     return text.replace(regex, '<span class="search-highlight">$1</span>');
   }
 
@@ -104,20 +344,26 @@
     return str.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
   }
 
-  // Display search results
-  function displayResults(results, query) {
+  // Display search results, grouped by page
+  function displayResults(groups, words) {
     const container = document.getElementById('search-results');
     selectedIndex = -1;
 
-    if (results.length === 0) {
+    if (groups.length === 0) {
       container.innerHTML = '<div class="no-results">No results found</div>';
       return;
     }
 
-    const html = results.map((result, index) => `
-      <div class="search-result" data-index="${index}" data-url="${result.url}">
-        <div class="search-result-title">${highlightText(result.title, query)}</div>
-        <div class="search-result-preview">${highlightText(result.preview, query)}</div>
+    let index = 0;
+    const html = groups.map((group) => `
+      <div class="search-result-group">
+        <div class="search-result-page">${highlightText(group.title, words)}</div>
+        ${group.results.map((result) => `
+          <div class="search-result" data-index="${index++}" data-url="${result.url}">
+            <div class="search-result-title">${highlightText(result.section || result.page, words)}</div>
+            <div class="search-result-preview">${highlightText(result.preview, words)}</div>
+          </div>
+        `).join('')}
       </div>
     `).join('');
 
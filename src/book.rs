@@ -1,8 +1,11 @@
-use crate::config::Config;
+use crate::config::{BuildConfig, Config};
 use anyhow::{Context, Result};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum BookItem {
     Part {
         title: String,
@@ -11,21 +14,25 @@ pub enum BookItem {
     Page(PageInfo),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Book {
     pub config: Config,
     pub items: Vec<BookItem>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PageInfo {
     pub title: String,
     pub source_path: PathBuf,
     pub output_filename: String,
+    /// Absolute URL path including base_path (e.g. "/book/guide/setup.html")
+    pub url: String,
     pub sections: Vec<Section>,
+    /// Words in the markdown source (each CJK character counts as one word)
+    pub word_count: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Section {
     pub title: String,
     pub id: String,
@@ -50,14 +57,14 @@ impl Book {
                             for item in explicit_items {
                                 let child_item = if item.path.is_empty() {
                                     // Nested Part
-                                    Self::parse_nested_part(item, base_dir, &config.build.src_dir)?
+                                    Self::parse_nested_part(item, base_dir, &config.build)?
                                 } else {
                                     // Page
                                     let page_info = Self::create_page_info(
-                                        &item.title,
+                                        item.title.as_deref(),
                                         &item.path,
                                         base_dir,
-                                        &config.build.src_dir,
+                                        &config.build,
                                     )?;
                                     BookItem::Page(page_info)
                                 };
@@ -76,10 +83,10 @@ impl Book {
                                 // Add this page as a child
                                 if let Some(path) = &next_config.path {
                                     let page_info = Self::create_page_info(
-                                        &next_config.title,
+                                        next_config.title.as_deref(),
                                         path,
                                         base_dir,
-                                        &config.build.src_dir,
+                                        &config.build,
                                     )?;
                                     children.push(BookItem::Page(page_info));
                                 }
@@ -91,17 +98,17 @@ impl Book {
                     }
 
                     items.push(BookItem::Part {
-                        title: page_config.title.clone(),
+                        title: page_config.title.clone().unwrap_or_default(),
                         children,
                     });
                 }
                 Some(path) => {
                     // Has path = standalone Page (not part of any part)
                     let page_info = Self::create_page_info(
-                        &page_config.title,
+                        page_config.title.as_deref(),
                         path,
                         base_dir,
-                        &config.build.src_dir,
+                        &config.build,
                     )?;
                     items.push(BookItem::Page(page_info));
                 }
@@ -110,13 +117,37 @@ impl Book {
             i += 1;
         }
 
+        // Titles may come from the Markdown, so duplicates are checked once resolved
+        let mut titles = std::collections::HashSet::new();
+        Self::check_duplicate_titles(&items, &mut titles)?;
+
         Ok(Self { config, items })
     }
 
+    /// Page titles must be unique; Part titles may repeat a page title
+    fn check_duplicate_titles<'a>(
+        items: &'a [BookItem],
+        titles: &mut std::collections::HashSet<&'a str>,
+    ) -> Result<()> {
+        for item in items {
+            match item {
+                BookItem::Part { children, .. } => {
+                    Self::check_duplicate_titles(children, titles)?;
+                }
+                BookItem::Page(page) => {
+                    if !titles.insert(&page.title) {
+                        anyhow::bail!("Duplicate page title: {}", page.title);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn parse_nested_part(
         item: &crate::config::PageItem,
         base_dir: &Path,
-        src_dir: &Path,
+        build: &BuildConfig,
     ) -> Result<BookItem> {
         let mut children = Vec::new();
 
@@ -124,11 +155,15 @@ impl Book {
             for child in child_items {
                 let child_item = if child.path.is_empty() {
                     // Recursively parse nested part
-                    Self::parse_nested_part(child, base_dir, src_dir)?
+                    Self::parse_nested_part(child, base_dir, build)?
                 } else {
                     // Page
-                    let page_info =
-                        Self::create_page_info(&child.title, &child.path, base_dir, src_dir)?;
+                    let page_info = Self::create_page_info(
+                        child.title.as_deref(),
+                        &child.path,
+                        base_dir,
+                        build,
+                    )?;
                     BookItem::Page(page_info)
                 };
                 children.push(child_item);
@@ -136,18 +171,19 @@ impl Book {
         }
 
         Ok(BookItem::Part {
-            title: item.title.clone(),
+            title: item.title.clone().unwrap_or_default(),
             children,
         })
     }
 
+    /// `title` falls back to front matter `title`, the first H1, then the file name
     fn create_page_info(
-        title: &str,
+        title: Option<&str>,
         path: &str,
         base_dir: &Path,
-        src_dir: &Path,
+        build: &BuildConfig,
     ) -> Result<PageInfo> {
-        let source_path = base_dir.join(src_dir).join(path);
+        let source_path = base_dir.join(&build.src_dir).join(path);
 
         // Validate that source file exists
         if !source_path.exists() {
@@ -161,18 +197,55 @@ impl Book {
         // Convert path to output filename
         let output_filename = Self::source_to_html_filename(path)?;
 
+        let content =
+            std::fs::read_to_string(&source_path).context("Failed to read markdown file")?;
+
         // Extract H2 sections from markdown
-        let sections = Self::extract_sections(&source_path)?;
+        let sections = Self::extract_sections(&content);
+        let word_count = count_words(&content);
+        let url = format!(
+            "{}/{}",
+            crate::toc::normalize_base_path(&build.base_path),
+            output_filename
+        );
+
+        let title = match title {
+            Some(title) => title.to_string(),
+            None => Self::infer_title(&content, path),
+        };
 
         Ok(PageInfo {
-            title: title.to_string(),
+            title,
             source_path,
             output_filename,
+            url,
             sections,
+            word_count,
         })
     }
 
-    fn source_to_html_filename(source_path: &str) -> Result<String> {
+    fn infer_title(content: &str, path: &str) -> String {
+        let (front_matter, body) = crate::meta::FrontMatter::parse(content);
+        if let Some(title) = front_matter.get("title") {
+            return title.to_string();
+        }
+
+        let mut in_code_block = false;
+        for line in body.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            } else if !in_code_block && let Some(h1) = line.strip_prefix("# ") {
+                return h1.trim().trim_end_matches('#').trim_end().to_string();
+            }
+        }
+
+        Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    pub(crate) fn source_to_html_filename(source_path: &str) -> Result<String> {
         // Validate that the source path ends with .md
         if !source_path.ends_with(".md") {
             anyhow::bail!("Source file must have .md extension: {}", source_path);
@@ -183,47 +256,119 @@ impl Book {
         Ok(html_filename)
     }
 
-    fn extract_sections(markdown_path: &Path) -> Result<Vec<Section>> {
-        let content =
-            std::fs::read_to_string(markdown_path).context("Failed to read markdown file")?;
-
+    /// H2 headings, ATX ("## Title") or setext ("Title" underlined with "---")
+    /// Code blocks and front matter are skipped, the same as in the search index
+    fn extract_sections(content: &str) -> Vec<Section> {
+        let (_, markdown) = crate::meta::FrontMatter::parse(content);
         let mut sections = Vec::new();
+        // First source line and text of the H2 being read
+        let mut heading: Option<(&str, String)> = None;
+
+        for (event, range) in Parser::new_ext(markdown, markdown_options()).into_offset_iter() {
+            match event {
+                Event::Start(Tag::Heading {
+                    level: HeadingLevel::H2,
+                    ..
+                }) => {
+                    let line = markdown[range].lines().next().unwrap_or_default();
+                    heading = Some((line, String::new()));
+                }
+                Event::Text(t) | Event::Code(t) => {
+                    if let Some((_, text)) = &mut heading {
+                        text.push_str(&t);
+                    }
+                }
+                Event::End(TagEnd::Heading(HeadingLevel::H2)) => {
+                    if let Some((line, text)) = heading.take() {
+                        let title = h2_title(line, &text);
+                        let id = section_id(&title);
+                        sections.push(Section { title, id });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        sections
+    }
+
+    pub fn output_dir(&self, base_dir: &Path) -> PathBuf {
+        base_dir.join(&self.config.build.output_dir)
+    }
 
-        for line in content.lines() {
-            // Check if line is an H2 heading (## Title)
-            if let Some(title) = line.strip_prefix("## ") {
-                let title = title.trim().to_string();
-                // Generate ID matching unidoc's format: "2-Title"
-                // unidoc percent-encodes all except alphanumeric, '-', and '_'
-                let id = format!("2-{}", Self::percent_encode(&title));
-                sections.push(Section { title, id });
+    /// Resolve a URL found in a page to a book-relative path
+    /// ("/base/a/b.html" and "../a/b.html" both become "a/b.html")
+    pub fn resolve_url(&self, page: &PageInfo, url: &str) -> String {
+        match url.strip_prefix('/') {
+            Some(absolute) => {
+                let base_path = crate::toc::normalize_base_path(&self.config.build.base_path);
+                absolute
+                    .strip_prefix(base_path.trim_start_matches('/'))
+                    .map(|p| p.trim_start_matches('/'))
+                    .unwrap_or(absolute)
+                    .to_string()
             }
+            None => crate::html::resolve_relative(&page.output_dir(), url),
         }
+    }
 
-        Ok(sections)
+    /// Locate a local file (e.g. an image) referenced from a page
+    /// Looks next to the markdown source, then in src_dir and output_dir
+    pub fn find_asset(&self, base_dir: &Path, page: &PageInfo, url: &str) -> Option<PathBuf> {
+        let book_path = self.resolve_url(page, url);
+        let mut candidates = Vec::new();
+        if !url.starts_with('/')
+            && let Some(source_dir) = page.source_path.parent()
+        {
+            candidates.push(source_dir.join(url));
+        }
+        candidates.push(base_dir.join(&self.config.build.src_dir).join(&book_path));
+        candidates.push(self.output_dir(base_dir).join(&book_path));
+        candidates.into_iter().find(|path| path.is_file())
     }
 
-    fn percent_encode(input: &str) -> String {
-        // Match unidoc's encoding: NON_ALPHANUMERIC minus '-' and '_'
-        // This means: encode everything except [a-zA-Z0-9-_]
-        input
-            .chars()
-            .map(|c| {
-                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
-                    c.to_string()
-                } else {
-                    // Percent encode the character
-                    c.to_string()
-                        .bytes()
-                        .map(|b| format!("%{:02X}", b))
-                        .collect::<String>()
+    /// Human-readable tree of parts, pages and sections
+    pub fn outline(&self) -> String {
+        let mut text = format!("{}\n", self.config.book.title);
+        Self::outline_items(&self.items, 1, &mut text);
+        text
+    }
+
+    fn outline_items(items: &[BookItem], depth: usize, text: &mut String) {
+        let indent = "  ".repeat(depth);
+        for item in items {
+            match item {
+                BookItem::Part { title, children } => {
+                    text.push_str(&format!("{}[{}]\n", indent, title));
+                    Self::outline_items(children, depth + 1, text);
                 }
-            })
-            .collect()
+                BookItem::Page(page) => {
+                    text.push_str(&format!(
+                        "{}{} ({}, {} words) -> {}\n",
+                        indent,
+                        page.title,
+                        page.source_path.display(),
+                        page.word_count,
+                        page.url
+                    ));
+                    for section in &page.sections {
+                        text.push_str(&format!("{}  #{} {}\n", indent, section.id, section.title));
+                    }
+                }
+            }
+        }
     }
 
-    pub fn output_dir(&self, base_dir: &Path) -> PathBuf {
-        base_dir.join(&self.config.build.output_dir)
+    /// Absolute URL of an output file: site_url + base_path + "/" + output_filename
+    /// Returns None when site_url is not configured
+    pub fn absolute_url(&self, output_filename: &str) -> Option<String> {
+        let site_url = self.config.book.site_url.as_ref()?;
+        Some(format!(
+            "{}{}/{}",
+            site_url.trim_end_matches('/'),
+            crate::toc::normalize_base_path(&self.config.build.base_path),
+            output_filename
+        ))
     }
 }
 
@@ -231,12 +376,120 @@ impl PageInfo {
     pub fn slug(&self) -> String {
         self.output_filename.replace(".html", "")
     }
+
+    /// Directory of the output file with a trailing "/" ("" for top-level pages)
+    pub fn output_dir(&self) -> String {
+        match self.output_filename.rsplit_once('/') {
+            Some((dir, _)) => format!("{}/", dir),
+            None => String::new(),
+        }
+    }
+}
+
+/// Markdown extensions enabled when parsing page sources
+pub fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH
+}
+
+/// Title of an H2 as written on its first source line, which unidoc derives
+/// the id from: "## Title" and a setext "Title" both give "Title"
+/// Other ATX forms (e.g. "##\tTitle") fall back to the heading text
+pub fn h2_title(first_line: &str, text: &str) -> String {
+    if let Some(title) = first_line.strip_prefix("## ") {
+        title.trim().to_string()
+    } else if !first_line.trim_start().starts_with('#') {
+        first_line.trim().to_string()
+    } else {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Anchor id unidoc generates for an H2 heading: "2-Title"
+/// unidoc percent-encodes all except alphanumeric, '-', and '_'
+pub fn section_id(title: &str) -> String {
+    format!("2-{}", percent_encode(title))
+}
+
+fn percent_encode(input: &str) -> String {
+    // Match unidoc's encoding: NON_ALPHANUMERIC minus '-' and '_'
+    // This means: encode everything except [a-zA-Z0-9-_]
+    input
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_string()
+            } else {
+                // Percent encode the character
+                c.to_string()
+                    .bytes()
+                    .map(|b| format!("%{:02X}", b))
+                    .collect::<String>()
+            }
+        })
+        .collect()
+}
+
+/// Count words in markdown, skipping fenced code blocks
+/// Runs of non-CJK characters count as one word; each CJK character counts as one
+pub fn count_words(markdown: &str) -> usize {
+    let mut count = 0;
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let mut in_word = false;
+        for c in line.chars() {
+            if is_cjk(c) {
+                count += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() {
+                if !in_word {
+                    count += 1;
+                }
+                in_word = true;
+            } else if c.is_whitespace() {
+                in_word = false;
+            }
+        }
+    }
+
+    count
+}
+
+/// Han (including 々 and the supplementary planes), Hiragana, Katakana
+/// and Hangul. Shared by word counts and the search tokenizer (mirrored by
+/// `CJK` in search.js).
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3005}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9F}'
+        | '\u{20000}'..='\u{2FA1F}'
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{BookConfig, BuildConfig, Config, PageConfig, TocConfig};
+    use crate::config::{
+        BookConfig, BuildConfig, CheckConfig, Config, LintConfig, OutputConfig, PageConfig,
+        SearchConfig, TocConfig,
+    };
 
     fn create_test_config() -> Config {
         Config {
@@ -246,26 +499,37 @@ mod tests {
                 authors: vec![],
                 language: "en".to_string(),
                 theme: "light".to_string(),
+                site_url: None,
+                image: None,
+                repository_url: None,
+                edit_url_template: None,
             },
             build: BuildConfig {
                 src_dir: PathBuf::from("src"),
                 output_dir: PathBuf::from("docs"),
                 base_path: String::new(),
+                strict: false,
             },
             toc: TocConfig {
                 show_sections: "current".to_string(),
                 foldlevel: 0,
             },
+            output: OutputConfig::default(),
+            check: CheckConfig::default(),
+            lint: LintConfig::default(),
+            search: SearchConfig::default(),
             pages: vec![
                 PageConfig {
-                    title: "Page 1".to_string(),
+                    title: Some("Page 1".to_string()),
                     path: Some("page1.md".to_string()),
                     items: None,
+                    level: None,
                 },
                 PageConfig {
-                    title: "Page 2".to_string(),
+                    title: Some("Page 2".to_string()),
                     path: Some("page2.md".to_string()),
                     items: None,
+                    level: None,
                 },
             ],
         }
@@ -340,17 +604,174 @@ mod tests {
         std::fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[test]
+    fn test_inferred_titles() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-book-titles");
+        let src_dir = temp_dir.join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("page1.md"),
+            "```\n# comment\n```\n# First H1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            src_dir.join("page2.md"),
+            "---\ntitle: From Front Matter\n---\n# H1\n",
+        )
+        .unwrap();
+        std::fs::write(src_dir.join("page3.md"), "No heading").unwrap();
+
+        let mut config = create_test_config();
+        for page in &mut config.pages {
+            page.title = None;
+        }
+        config.pages.push(PageConfig {
+            title: Some("Part".to_string()),
+            path: None,
+            items: Some(vec![crate::config::PageItem {
+                title: None,
+                path: "page3.md".to_string(),
+                items: None,
+                level: None,
+            }]),
+            level: None,
+        });
+        let book = Book::from_config(config, &temp_dir).unwrap();
+        let titles: Vec<&str> = crate::builder::Builder::collect_pages(&book.items)
+            .iter()
+            .map(|page| page.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["First H1", "From Front Matter", "page3"]);
+
+        // Duplicates are detected after resolution, including nested items
+        std::fs::write(src_dir.join("page3.md"), "# First H1\n").unwrap();
+        let mut config = create_test_config();
+        config.pages[0].title = None;
+        config.pages[1].title = Some("Part".to_string());
+        config.pages[1].path = None;
+        config.pages[1].items = Some(vec![crate::config::PageItem {
+            title: None,
+            path: "page3.md".to_string(),
+            items: None,
+            level: None,
+        }]);
+        let error = Book::from_config(config, &temp_dir).unwrap_err();
+        assert!(error.to_string().contains("Duplicate page title: First H1"));
+
+        // A Part may share its title with a page inside it
+        std::fs::write(src_dir.join("page3.md"), "# Part\n").unwrap();
+        let mut config = create_test_config();
+        config.pages[1].title = Some("Part".to_string());
+        config.pages[1].path = None;
+        config.pages[1].items = Some(vec![crate::config::PageItem {
+            title: None,
+            path: "page3.md".to_string(),
+            items: None,
+            level: None,
+        }]);
+        assert!(Book::from_config(config, &temp_dir).is_ok());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_extract_sections() {
+        let content = concat!(
+            "---\ntitle: Guide\n---\n",
+            "# Guide\n## Setup\n```sh\n## not a heading\n```\n",
+            "Usage `cli`\n---\n\ntext\n\n---\n",
+        );
+        let sections = Book::extract_sections(content);
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Setup", "Usage `cli`"]);
+
+        // The TOC lists the same sections and anchors
+        let page = PageInfo {
+            title: "Guide".to_string(),
+            source_path: PathBuf::from("src/guide.md"),
+            output_filename: "guide.html".to_string(),
+            url: "/guide.html".to_string(),
+            sections,
+            word_count: 0,
+        };
+        let toc = crate::toc::TocGenerator::new(
+            "Book".to_string(),
+            "always".to_string(),
+            String::new(),
+            1,
+        )
+        .generate_toc_html(&[BookItem::Page(page)], None);
+        assert!(toc.contains("<a href=\"/guide.html#2-Setup\">Setup</a>"));
+        assert!(toc.contains("<a href=\"/guide.html#2-Usage%20%60cli%60\">Usage `cli`</a>"));
+        assert!(!toc.contains("not a heading"));
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("Hello, world! It's fine."), 4);
+        assert_eq!(count_words("日本語の本"), 5);
+        assert_eq!(count_words("Rust は速い"), 4);
+        assert_eq!(count_words("text\n```rust\nfn main() {}\n```\nmore"), 2);
+    }
+
+    #[test]
+    fn test_metadata_json() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-book-metadata");
+        let src_dir = temp_dir.join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(
+            src_dir.join("page1.md"),
+            "# Page 1\n\n## Setup\n\nSome words here",
+        )
+        .unwrap();
+        std::fs::write(src_dir.join("page2.md"), "# Page 2").unwrap();
+
+        let mut config = create_test_config();
+        config.build.base_path = "book".to_string();
+        let book = Book::from_config(config, &temp_dir).unwrap();
+        let json = serde_json::to_value(&book).unwrap();
+
+        let page = &json["items"][0];
+        assert_eq!(page["type"], "page");
+        assert_eq!(page["url"], "/book/page1.html");
+        assert_eq!(page["word_count"], 6);
+        assert_eq!(page["sections"][0]["id"], "2-Setup");
+        assert_eq!(json["config"]["book"]["title"], "Test Book");
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn test_page_info_slug() {
         let page = PageInfo {
             title: "Test Page".to_string(),
             source_path: PathBuf::from("test.md"),
             output_filename: "test.html".to_string(),
+            url: "/test.html".to_string(),
             sections: vec![],
+            word_count: 0,
         };
         assert_eq!(page.slug(), "test");
     }
 
+    #[test]
+    fn test_absolute_url() {
+        let mut config = create_test_config();
+        config.pages.clear();
+        let mut book = Book {
+            config,
+            items: vec![],
+        };
+        assert_eq!(book.absolute_url("intro.html"), None);
+
+        book.config.book.site_url = Some("https://example.com/".to_string());
+        book.config.build.base_path = "docs-book".to_string();
+        assert_eq!(
+            book.absolute_url("a/intro.html"),
+            Some("https://example.com/docs-book/a/intro.html".to_string())
+        );
+    }
+
     #[test]
     fn test_output_dir() {
         let config = create_test_config();
@@ -1,14 +1,46 @@
 use crate::book::Book;
+use crate::log;
+use crate::not_found::NotFoundPage;
+use crate::print::PrintPage;
 use crate::toc::TocGenerator;
 use crate::unidoc::UnidocCommand;
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Output format of `unibook build --format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// HTML site (default)
+    Html,
+    /// EPUB 3 file
+    Epub,
+    /// Self-contained single HTML file
+    SingleHtml,
+}
+
+/// Links verified by `unibook check` / `build --check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkCheck {
+    /// Internal links, anchors and images
+    Internal,
+    /// Internal links plus http(s) URLs
+    External,
+}
+
 pub struct Builder {
     book: Book,
     base_dir: PathBuf,
     temp_dir: PathBuf,
+    /// Fail the build on warnings (e.g. orphan files)
+    deny_warnings: bool,
+    /// Last updated date and contributors per page, looked up once per build
+    history: crate::history::History,
+    /// Where to write the JSON build report (`build --report`)
+    report_path: Option<PathBuf>,
+    /// Render the remaining pages when one fails and report all failures at the end
+    keep_going: bool,
 }
 
 impl Builder {
@@ -20,10 +52,59 @@ impl Builder {
             book,
             base_dir: base_dir.to_path_buf(),
             temp_dir,
+            deny_warnings: false,
+            history: crate::history::History::default(),
+            report_path: None,
+            keep_going: false,
         })
     }
 
+    pub fn deny_warnings(mut self, deny: bool) -> Self {
+        self.deny_warnings = deny;
+        self
+    }
+
+    pub fn report(mut self, path: Option<PathBuf>) -> Self {
+        self.report_path = path;
+        self
+    }
+
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.keep_going = keep_going;
+        self
+    }
+
+    pub fn build_format(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Html => self.build(),
+            OutputFormat::Epub => {
+                let filename = self
+                    .book
+                    .config
+                    .output
+                    .epub
+                    .as_ref()
+                    .map(|epub| epub.filename.clone())
+                    .unwrap_or_else(|| crate::config::EpubConfig::default().filename);
+                let failures = self.build_epub(&filename)?;
+                self.cleanup()?;
+                if failures.is_empty() {
+                    return Ok(());
+                }
+                let failures = crate::report::BuildFailures(failures);
+                log::error!("{}", failures.summary().trim_end());
+                Err(failures.into())
+            }
+            OutputFormat::SingleHtml => {
+                self.build_single_html()?;
+                self.cleanup()
+            }
+        }
+    }
+
     pub fn build(&self) -> Result<()> {
+        let started = std::time::Instant::now();
+
         // Create output directory
         let output_dir = self.book.output_dir(&self.base_dir);
         fs::create_dir_all(&output_dir).context("Failed to create output directory")?;
@@ -41,13 +122,14 @@ impl Builder {
 
         // Collect all pages recursively
         let all_pages = Self::collect_pages(&self.book.items);
+        let mut page_reports = Vec::new();
+        let mut failures = Vec::new();
 
         for page in &all_pages {
-            println!(
-                "Building: {} -> {}",
-                page.source_path.display(),
-                page.output_filename
-            );
+            log::event(log::Event::PageStarted {
+                source_path: &page.source_path,
+                output_filename: &page.output_filename,
+            });
 
             // Generate TOC with current page highlighted
             let toc_html = toc_gen.generate_toc_html(&self.book.items, Some(&page.output_filename));
@@ -64,14 +146,41 @@ impl Builder {
                 fs::create_dir_all(parent).context("Failed to create output subdirectories")?;
             }
 
-            self.build_page(page, &toc_path, &output_file)?;
+            match self.build_page(page, &toc_path, &output_file) {
+                Ok(report) => page_reports.push(report),
+                Err(e) if self.keep_going => {
+                    failures.push(crate::report::PageFailure::new(page, &e));
+                }
+                Err(e) => return Err(e),
+            }
         }
 
         // Generate search index
-        println!("Generating search index...");
-        crate::search::SearchIndexGenerator::generate(&self.book, &output_dir)
+        log::info!("Generating search index...");
+        crate::search::SearchIndexGenerator::generate(&self.book, &self.history, &output_dir)
             .context("Failed to generate search index")?;
 
+        // Generate llms.txt, llms-full.txt and .md mirrors
+        crate::llms::LlmsGenerator::generate(&self.book, &output_dir)
+            .context("Failed to generate llms.txt")?;
+        log::info!("Created llms.txt and llms-full.txt");
+
+        // Generate sitemap.xml and robots.txt
+        let written =
+            crate::sitemap::SitemapGenerator::generate(&self.book, &self.history, &output_dir)
+                .context("Failed to generate sitemap")?;
+        if !written.is_empty() {
+            log::info!("Created {}", written.join(" and "));
+        }
+
+        // Generate print.html
+        self.build_print_page(&toc_gen, &all_pages, &output_dir)?;
+        log::info!("Created print.html");
+
+        // Generate 404.html
+        self.build_not_found_page(&toc_gen, &all_pages, &output_dir)?;
+        log::info!("Created 404.html");
+
         // Generate index.html that redirects to first page
         let first_page = all_pages.first();
 
@@ -92,15 +201,67 @@ impl Builder {
                 first_page.output_filename, first_page.output_filename, self.book.config.book.title
             );
             fs::write(&index_path, index_content).context("Failed to create index.html")?;
-            println!("Created index.html");
+            log::info!("Created index.html");
+        }
+
+        // Generate EPUB if [output.epub] is configured
+        // (pages that fail here already failed above and are in the summary)
+        if let Some(epub) = &self.book.config.output.epub {
+            self.build_epub(&epub.filename)?;
+        }
+
+        log::info!("\nBuild complete! Output in: {}", output_dir.display());
+        let unidoc_warnings: usize = page_reports.iter().map(|p| p.warnings.len()).sum();
+
+        if let Some(report_path) = &self.report_path {
+            let search_index_bytes = crate::search::SearchIndexGenerator::output_bytes(&output_dir);
+            let report = crate::report::BuildReport::new(
+                page_reports,
+                started.elapsed(),
+                search_index_bytes,
+            );
+            report.write(report_path)?;
+            log::info!("\n{}", report.summary().trim_end());
+            log::info!("Wrote build report to {}", report_path.display());
         }
 
-        println!("\nBuild complete! Output in: {}", output_dir.display());
         self.cleanup()?;
+
+        // The failure summary is printed even when a warning check fails below
+        let failures = (!failures.is_empty()).then_some(crate::report::BuildFailures(failures));
+        if let Some(failures) = &failures {
+            log::error!("{}", failures.summary().trim_end());
+        }
+
+        self.report_orphans()?;
+        if self.deny_warnings && unidoc_warnings > 0 {
+            anyhow::bail!("unidoc reported {} warning(s)", unidoc_warnings);
+        }
+        match failures {
+            Some(failures) => Err(failures.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Warn about files under src_dir that no page uses
+    /// Fails instead when deny_warnings is set
+    fn report_orphans(&self) -> Result<()> {
+        let orphans = crate::orphans::find_orphans(&self.book, &self.base_dir)
+            .context("Failed to detect orphan files")?;
+        for orphan in &orphans {
+            if self.deny_warnings {
+                log::error!("{}", orphan);
+            } else {
+                log::warning!("{}", orphan);
+            }
+        }
+        if self.deny_warnings && !orphans.is_empty() {
+            anyhow::bail!("Found {} orphan file(s)", orphans.len());
+        }
         Ok(())
     }
 
-    fn collect_pages(items: &[crate::book::BookItem]) -> Vec<&crate::book::PageInfo> {
+    pub fn collect_pages(items: &[crate::book::BookItem]) -> Vec<&crate::book::PageInfo> {
         let mut pages = Vec::new();
         for item in items {
             match item {
@@ -160,11 +321,10 @@ impl Builder {
         });
 
         if let Some(page) = changed_page {
-            println!(
-                "Building: {} -> {}",
-                page.source_path.display(),
-                page.output_filename
-            );
+            log::event(log::Event::PageStarted {
+                source_path: &page.source_path,
+                output_filename: &page.output_filename,
+            });
 
             // Generate TOC with current page highlighted
             let toc_gen = TocGenerator::new(
@@ -187,15 +347,17 @@ impl Builder {
             self.build_page(page, &toc_path, &output_file)?;
 
             // Regenerate search index (this is relatively fast)
-            println!("Updating search index...");
-            crate::search::SearchIndexGenerator::generate(&self.book, &output_dir)
+            log::info!("Updating search index...");
+            crate::search::SearchIndexGenerator::generate(&self.book, &self.history, &output_dir)
                 .context("Failed to generate search index")?;
+            crate::llms::LlmsGenerator::generate(&self.book, &output_dir)
+                .context("Failed to generate llms.txt")?;
 
-            println!("\nIncremental build complete!");
+            log::info!("\nIncremental build complete!");
         } else {
             // File is not in pages list, might be a new file or book.toml changed
             // Do a full rebuild
-            println!("File not in current page list, doing full rebuild...");
+            log::info!("File not in current page list, doing full rebuild...");
             return self.build();
         }
 
@@ -226,10 +388,22 @@ impl Builder {
         fs::write(&theme_switcher_css_path, theme_switcher_css)
             .context("Failed to write theme switcher CSS")?;
 
+        fs::write(
+            self.temp_dir.join("content-start.html"),
+            crate::check::CONTENT_START,
+        )
+        .context("Failed to write content marker")?;
+        fs::write(
+            self.temp_dir.join("content-end.html"),
+            crate::check::CONTENT_END,
+        )
+        .context("Failed to write content marker")?;
+
         let theme_meta_path = self.temp_dir.join("theme-meta.html");
         let theme_meta = format!(
-            r#"<meta name="unibook-theme" content="{}">"#,
-            self.book.config.book.theme
+            r#"<meta name="unibook-theme" content="{}">{}"#,
+            self.book.config.book.theme,
+            crate::search::SearchIndexGenerator::manifest_meta(&self.book)
         );
         fs::write(&theme_meta_path, theme_meta).context("Failed to write theme meta")?;
 
@@ -298,6 +472,37 @@ impl Builder {
         )
         .context("Failed to write page controls end")?;
 
+        let print_css_path = self.temp_dir.join("print-style.html");
+        let print_css = format!(
+            "<style>{}</style>",
+            crate::search_assets::SearchAssets::print_css()
+        );
+        fs::write(&print_css_path, print_css).context("Failed to write print CSS")?;
+
+        let print_control_path = self.temp_dir.join("print-control.html");
+        fs::write(
+            &print_control_path,
+            PrintPage::control_html(&crate::toc::normalize_base_path(
+                &self.book.config.build.base_path,
+            )),
+        )
+        .context("Failed to write print control")?;
+
+        let copy_markdown_html_path = self.temp_dir.join("copy-markdown.html");
+        fs::write(
+            &copy_markdown_html_path,
+            crate::search_assets::SearchAssets::copy_markdown_html(),
+        )
+        .context("Failed to write copy Markdown HTML")?;
+
+        let copy_markdown_js_path = self.temp_dir.join("copy-markdown-script.html");
+        let copy_markdown_js = format!(
+            "<script>{}</script>",
+            crate::search_assets::SearchAssets::copy_markdown_js()
+        );
+        fs::write(&copy_markdown_js_path, copy_markdown_js)
+            .context("Failed to write copy Markdown JS")?;
+
         let search_html_path = self.temp_dir.join("search.html");
         fs::write(
             &search_html_path,
@@ -335,7 +540,94 @@ impl Builder {
         page: &crate::book::PageInfo,
         toc_path: &Path,
         output_file: &Path,
-    ) -> Result<()> {
+    ) -> Result<crate::report::PageReport> {
+        // Per-page <head> tags (canonical link, description, OpenGraph, ...)
+        let slug = page.slug().replace(['/', '\\'], "_");
+        let page_head_path = self.temp_dir.join(format!("page-head-{}.html", slug));
+        fs::write(&page_head_path, self.page_head_html(page))
+            .context("Failed to write page head")?;
+
+        // "Last updated" and contributors below the content
+        let footer_path = self.temp_dir.join(format!("page-footer-{}.html", slug));
+        let history = self.history.page(&page.source_path);
+        fs::write(&footer_path, crate::history::footer_html(&history))
+            .context("Failed to write page footer")?;
+
+        // "Edit this page" / "View source" links in the page controls
+        let edit_link_path = self.temp_dir.join(format!("edit-link-{}.html", slug));
+        fs::write(
+            &edit_link_path,
+            crate::edit_link::EditLink::controls_html(&self.book, page, &self.base_dir),
+        )
+        .context("Failed to write edit link")?;
+
+        let command = self
+            .unidoc_command(toc_path, true, Some(edit_link_path), Some(footer_path))
+            .include_in_header(page_head_path)
+            .output(output_file.to_path_buf());
+        let started = std::time::Instant::now();
+        let warnings = match command.execute(&page.source_path) {
+            Ok(warnings) => warnings,
+            Err(e) => {
+                log::event(log::Event::PageFailed {
+                    source_path: &page.source_path,
+                    output_filename: &page.output_filename,
+                    error: e.to_string(),
+                });
+                return Err(e.context(format!("Failed to build page: {}", page.title)));
+            }
+        };
+        let unidoc_ms = started.elapsed().as_millis() as u64;
+        for warning in &warnings {
+            log::warning!("{}: {}", page.source_path.display(), warning);
+        }
+        log::event(log::Event::PageFinished {
+            source_path: &page.source_path,
+            output_filename: &page.output_filename,
+            duration_ms: unidoc_ms,
+        });
+
+        self.add_lang_attribute(output_file)?;
+
+        let size = |path: &Path| fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let toc_bytes = size(toc_path);
+        Ok(crate::report::PageReport {
+            title: page.title.clone(),
+            source_path: page.source_path.clone(),
+            output_filename: page.output_filename.clone(),
+            unidoc_ms,
+            output_bytes: size(output_file),
+            inlined_bytes: command.included_bytes().saturating_sub(toc_bytes),
+            toc_bytes,
+            word_count: page.word_count,
+            warnings,
+        })
+    }
+
+    fn page_head_html(&self, page: &crate::book::PageInfo) -> String {
+        let mut html = String::new();
+        if let Some(url) = self.book.absolute_url(&page.output_filename) {
+            html.push_str(&format!(
+                "<link rel=\"canonical\" href=\"{}\">\n",
+                crate::toc::attr_escape(&url)
+            ));
+        }
+        html.push_str(&crate::meta::MetaTags::generate(&self.book, page));
+        html
+    }
+
+    /// unidoc command with the book chrome (TOC, theme, search, controls)
+    /// `document_controls` adds the print and copy-as-Markdown buttons
+    /// (off for generated pages, which have no `.md` mirror), `page_controls`
+    /// is added to the page control buttons, `footer` is placed right after
+    /// the page content
+    fn unidoc_command(
+        &self,
+        toc_path: &Path,
+        document_controls: bool,
+        page_controls: Option<PathBuf>,
+        footer: Option<PathBuf>,
+    ) -> UnidocCommand {
         let theme_meta_path = self.temp_dir.join("theme-meta.html");
         let theme_css_path = self.temp_dir.join("theme-style.html");
         let theme_switcher_css_path = self.temp_dir.join("theme-switcher-style.html");
@@ -343,19 +635,25 @@ impl Builder {
         let code_copy_css_path = self.temp_dir.join("code-copy-style.html");
         let css_path = self.temp_dir.join("style.html");
         let search_css_path = self.temp_dir.join("search-style.html");
+        let print_css_path = self.temp_dir.join("print-style.html");
         let page_controls_start_path = self.temp_dir.join("page-controls-start.html");
         let toc_toggle_html_path = self.temp_dir.join("toc-toggle.html");
         let theme_switcher_html_path = self.temp_dir.join("theme-switcher.html");
+        let print_control_path = self.temp_dir.join("print-control.html");
+        let copy_markdown_html_path = self.temp_dir.join("copy-markdown.html");
         let page_controls_end_path = self.temp_dir.join("page-controls-end.html");
         let search_html_path = self.temp_dir.join("search.html");
         let theme_switcher_js_path = self.temp_dir.join("theme-switcher-script.html");
         let search_js_path = self.temp_dir.join("search-script.html");
         let toc_toggle_js_path = self.temp_dir.join("toc-toggle-script.html");
         let code_copy_js_path = self.temp_dir.join("code-copy-script.html");
+        let copy_markdown_js_path = self.temp_dir.join("copy-markdown-script.html");
         let prism_retry_js_path = self.temp_dir.join("prism-retry-script.html");
         let wrapper_end_path = self.temp_dir.join("wrapper-end.html");
+        let content_start_path = self.temp_dir.join("content-start.html");
+        let content_end_path = self.temp_dir.join("content-end.html");
 
-        UnidocCommand::new()
+        let mut command = UnidocCommand::new()
             .standalone()
             .include_in_header(theme_meta_path)
             .include_in_header(theme_css_path)
@@ -364,23 +662,254 @@ impl Builder {
             .include_in_header(code_copy_css_path)
             .include_in_header(css_path)
             .include_in_header(search_css_path)
+            .include_in_header(print_css_path)
             .include_before_body(toc_path.to_path_buf())
             .include_before_body(page_controls_start_path)
             .include_before_body(toc_toggle_html_path)
-            .include_before_body(theme_switcher_html_path)
+            .include_before_body(theme_switcher_html_path);
+        if document_controls {
+            command = command
+                .include_before_body(print_control_path)
+                .include_before_body(copy_markdown_html_path);
+        }
+        if let Some(page_controls) = page_controls {
+            command = command.include_before_body(page_controls);
+        }
+        command = command
             .include_before_body(page_controls_end_path)
             .include_before_body(search_html_path)
+            .include_before_body(content_start_path)
+            .include_after_body(content_end_path);
+        if let Some(footer) = footer {
+            command = command.include_after_body(footer);
+        }
+        command
             .include_after_body(theme_switcher_js_path)
             .include_after_body(search_js_path)
             .include_after_body(toc_toggle_js_path)
             .include_after_body(code_copy_js_path)
+            .include_after_body(copy_markdown_js_path)
             .include_after_body(prism_retry_js_path)
             .include_after_body(wrapper_end_path)
-            .output(output_file.to_path_buf())
+    }
+
+    /// Render a page body with unidoc, without the book chrome
+    fn render_fragment(&self, page: &crate::book::PageInfo) -> Result<String> {
+        let slug = page.slug().replace(['/', '\\'], "_");
+        let fragment_path = self.temp_dir.join(format!("fragment-{}.html", slug));
+        UnidocCommand::new()
+            .output(fragment_path.clone())
             .execute(&page.source_path)
-            .context(format!("Failed to build page: {}", page.title))?;
+            .context(format!("Failed to render page: {}", page.title))?;
+        fs::read_to_string(&fragment_path).context("Failed to read rendered page")
+    }
 
-        self.add_lang_attribute(output_file)?;
+    /// Verify internal links, anchors and images of the built HTML pages
+    pub fn check_links(&self, check: LinkCheck) -> Result<()> {
+        log::info!("Checking links...");
+        let output_dir = self.book.output_dir(&self.base_dir);
+        let mut checker = crate::check::LinkChecker::new(&self.book, &output_dir);
+        let mut broken = checker.check()?;
+
+        if check == LinkCheck::External {
+            let external =
+                crate::external::ExternalChecker::new(&self.book.config.check, &self.base_dir);
+            broken.extend(external.check(checker.external_links())?);
+        }
+
+        if broken.is_empty() {
+            log::info!("All links OK");
+            return Ok(());
+        }
+        for link in &broken {
+            log::error!("{}", link);
+        }
+        anyhow::bail!("Found {} broken link(s)", broken.len())
+    }
+
+    /// Package all pages into an EPUB file in the output directory
+    /// Returns the pages left out under keep_going
+    pub fn build_epub(&self, filename: &str) -> Result<Vec<crate::report::PageFailure>> {
+        let output_dir = self.book.output_dir(&self.base_dir);
+        fs::create_dir_all(&output_dir).context("Failed to create output directory")?;
+
+        let all_pages = Self::collect_pages(&self.book.items);
+        let (fragments, failures) = render_pages(&all_pages, self.keep_going, |page| {
+            log::info!("Rendering for EPUB: {}", page.source_path.display());
+            self.render_fragment(page)
+        })?;
+
+        let epub_path = output_dir.join(filename);
+        crate::epub::EpubWriter::new(&self.book, &self.base_dir)
+            .write(&fragments, &epub_path)
+            .context("Failed to generate EPUB")?;
+        log::info!("Created {}", epub_path.display());
+        Ok(failures)
+    }
+
+    /// Generate a self-contained single.html: every page as a section,
+    /// images as data URIs and the search index embedded
+    pub fn build_single_html(&self) -> Result<()> {
+        let output_dir = self.book.output_dir(&self.base_dir);
+        fs::create_dir_all(&output_dir).context("Failed to create output directory")?;
+        self.generate_assets()?;
+
+        let base_path = crate::toc::normalize_base_path(&self.book.config.build.base_path);
+        let all_pages = Self::collect_pages(&self.book.items);
+
+        let mut fragments = HashMap::new();
+        for page in &all_pages {
+            log::info!("Rendering for single HTML: {}", page.source_path.display());
+            let fragment = self.render_fragment(page)?;
+            let fragment = crate::single_html::SingleHtml::embed_images(
+                &self.book,
+                &self.base_dir,
+                page,
+                &fragment,
+            );
+            fragments.insert(
+                page.output_filename.clone(),
+                PrintPage::rewrite_fragment(&fragment, page, &all_pages, &base_path),
+            );
+        }
+
+        // TOC links point to the sections in the same document
+        let toc_gen = TocGenerator::new(
+            self.book.config.book.title.clone(),
+            self.book.config.toc.show_sections.clone(),
+            self.book.config.build.base_path.clone(),
+            self.book.config.toc.foldlevel,
+        );
+        let toc_html = PrintPage::rewrite_links(
+            &toc_gen.generate_toc_html(&self.book.items, None),
+            "",
+            None,
+            &all_pages,
+            &base_path,
+        );
+        let toc_path = self.temp_dir.join("toc-single.html");
+        fs::write(&toc_path, toc_html).context("Failed to write TOC file")?;
+
+        let body_path = self.temp_dir.join("single-body.html");
+        fs::write(
+            &body_path,
+            PrintPage::assemble(&self.book.items, &fragments),
+        )
+        .context("Failed to write single HTML body")?;
+
+        let mut index =
+            crate::search::SearchIndexGenerator::build_index(&self.book, &self.history)?;
+        let base_path = format!(
+            "{}/",
+            crate::toc::normalize_base_path(&self.book.config.build.base_path)
+        );
+        index.map_urls(|url| {
+            let (page, fragment) = url.split_once('#').unwrap_or((url, ""));
+            let output_filename = page.strip_prefix(&base_path).unwrap_or(page);
+            PrintPage::in_document_link(&PrintPage::page_anchor(output_filename), fragment)
+        });
+        let search_path = self.temp_dir.join("single-search-index.html");
+        fs::write(
+            &search_path,
+            crate::single_html::SingleHtml::search_script(&index)?,
+        )
+        .context("Failed to write embedded search index")?;
+
+        let source_path = self.temp_dir.join("single.md");
+        fs::write(&source_path, "").context("Failed to write single HTML source")?;
+
+        let output_file = output_dir.join("single.html");
+        // print.html and the .md mirrors do not exist next to the exported file
+        self.unidoc_command(&toc_path, false, None, None)
+            .include_before_body(body_path)
+            .include_after_body(search_path)
+            .output(output_file.clone())
+            .execute(&source_path)
+            .context("Failed to build single.html")?;
+
+        self.add_lang_attribute(&output_file)?;
+        log::info!("Created {}", output_file.display());
+        Ok(())
+    }
+
+    /// Generate print.html: every page in TOC order on a single page
+    fn build_print_page(
+        &self,
+        toc_gen: &TocGenerator,
+        pages: &[&crate::book::PageInfo],
+        output_dir: &Path,
+    ) -> Result<()> {
+        let base_path = crate::toc::normalize_base_path(&self.book.config.build.base_path);
+
+        // Render each page body without the chrome; a page that fails here
+        // failed its own build the same way and is in the failure summary
+        let (fragments, _) = render_pages(pages, self.keep_going, |page| {
+            let fragment = self.render_fragment(page)?;
+            Ok(PrintPage::rewrite_fragment(
+                &fragment, page, pages, &base_path,
+            ))
+        })?;
+
+        let toc_path = self.temp_dir.join("toc-print.html");
+        fs::write(&toc_path, toc_gen.generate_toc_html(&self.book.items, None))
+            .context("Failed to write TOC file")?;
+
+        let body_path = self.temp_dir.join("print-body.html");
+        fs::write(
+            &body_path,
+            PrintPage::assemble(&self.book.items, &fragments),
+        )
+        .context("Failed to write print body")?;
+
+        // The content is included before the body, so the source itself is empty
+        let source_path = self.temp_dir.join("print.md");
+        fs::write(&source_path, "").context("Failed to write print source")?;
+
+        let output_file = output_dir.join("print.html");
+        // Printing print.html is the browser's job, and it has no .md mirror
+        self.unidoc_command(&toc_path, false, None, None)
+            .include_before_body(body_path)
+            .output(output_file.clone())
+            .execute(&source_path)
+            .context("Failed to build print.html")?;
+
+        self.add_lang_attribute(&output_file)?;
+        Ok(())
+    }
+
+    /// Generate 404.html with the book chrome and "did you mean" suggestions
+    fn build_not_found_page(
+        &self,
+        toc_gen: &TocGenerator,
+        pages: &[&crate::book::PageInfo],
+        output_dir: &Path,
+    ) -> Result<()> {
+        let base_path = crate::toc::normalize_base_path(&self.book.config.build.base_path);
+
+        let toc_path = self.temp_dir.join("toc-404.html");
+        fs::write(&toc_path, toc_gen.generate_toc_html(&self.book.items, None))
+            .context("Failed to write TOC file")?;
+
+        let source_path = self.temp_dir.join("404.md");
+        fs::write(&source_path, NotFoundPage::markdown()).context("Failed to write 404 source")?;
+
+        let base_tag_path = self.temp_dir.join("not-found-base.html");
+        fs::write(&base_tag_path, NotFoundPage::base_tag(&base_path))
+            .context("Failed to write 404 base tag")?;
+
+        let script_path = self.temp_dir.join("not-found-script.html");
+        fs::write(&script_path, NotFoundPage::script(pages, &base_path)?)
+            .context("Failed to write 404 script")?;
+
+        let output_file = output_dir.join("404.html");
+        self.unidoc_command(&toc_path, false, None, None)
+            .include_in_header(base_tag_path)
+            .include_after_body(script_path)
+            .output(output_file.clone())
+            .execute(&source_path)
+            .context("Failed to build 404.html")?;
+
+        self.add_lang_attribute(&output_file)?;
         Ok(())
     }
 
@@ -397,3 +926,64 @@ impl Drop for Builder {
         let _ = self.cleanup();
     }
 }
+
+/// Render each page body with `render`, keyed by output filename
+/// Under keep_going a failed page is left out and returned as a failure
+/// instead of stopping at the first error
+fn render_pages(
+    pages: &[&crate::book::PageInfo],
+    keep_going: bool,
+    mut render: impl FnMut(&crate::book::PageInfo) -> Result<String>,
+) -> Result<(HashMap<String, String>, Vec<crate::report::PageFailure>)> {
+    let mut fragments = HashMap::new();
+    let mut failures = Vec::new();
+    for page in pages {
+        match render(page) {
+            Ok(fragment) => {
+                fragments.insert(page.output_filename.clone(), fragment);
+            }
+            Err(e) if keep_going => failures.push(crate::report::PageFailure::new(page, &e)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((fragments, failures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::PageInfo;
+
+    fn page(output_filename: &str) -> PageInfo {
+        PageInfo {
+            title: output_filename.to_string(),
+            source_path: PathBuf::from(output_filename.replace(".html", ".md")),
+            output_filename: output_filename.to_string(),
+            url: format!("/{}", output_filename),
+            sections: vec![],
+            word_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_pages_keep_going() {
+        let (a, b, c) = (page("a.html"), page("b.html"), page("c.html"));
+        let pages = vec![&a, &b, &c];
+        let render = |page: &PageInfo| {
+            if page.output_filename == "b.html" {
+                anyhow::bail!("unidoc failed");
+            }
+            Ok(format!("<p>{}</p>", page.title))
+        };
+
+        let (fragments, failures) = render_pages(&pages, true, render).unwrap();
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments["c.html"], "<p>c.html</p>");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].source_path, PathBuf::from("b.md"));
+        assert_eq!(failures[0].error, "unidoc failed");
+
+        let error = render_pages(&pages, false, render).unwrap_err();
+        assert_eq!(error.to_string(), "unidoc failed");
+    }
+}
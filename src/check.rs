@@ -0,0 +1,304 @@
+use crate::book::{Book, PageInfo};
+use crate::external::ExternalLink;
+use crate::html::percent_decode;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Comments the builder puts around the rendered page body, so that the
+/// generated chrome (TOC, page controls with edit links, search modal,
+/// footer) is not checked
+pub const CONTENT_START: &str = "<!-- unibook:content -->";
+pub const CONTENT_END: &str = "<!-- /unibook:content -->";
+
+/// A link or image in a rendered page that does not resolve
+#[derive(Debug)]
+pub struct BrokenLink {
+    /// Markdown source of the page containing the link
+    pub source_path: PathBuf,
+    /// Line in the markdown source, when the link text can be found there
+    pub line: Option<usize>,
+    pub url: String,
+    pub reason: String,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: ", self.source_path.display(), line)?,
+            None => write!(f, "{}: ", self.source_path.display())?,
+        }
+        write!(f, "broken link `{}` ({})", self.url, self.reason)
+    }
+}
+
+/// Checks internal hrefs, `#fragment` anchors and image sources of rendered pages
+/// against the files in output_dir and the ids of their elements
+pub struct LinkChecker<'a> {
+    book: &'a Book,
+    output_dir: PathBuf,
+    /// Element ids per output file (book-relative path), loaded on demand
+    ids: HashMap<String, HashSet<String>>,
+    /// http(s) URLs seen while checking, for `check --external`
+    external: Vec<ExternalLink>,
+}
+
+impl<'a> LinkChecker<'a> {
+    pub fn new(book: &'a Book, output_dir: &Path) -> Self {
+        Self {
+            book,
+            output_dir: output_dir.to_path_buf(),
+            ids: HashMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    /// External links collected by the last `check`
+    pub fn external_links(&self) -> &[ExternalLink] {
+        &self.external
+    }
+
+    pub fn check(&mut self) -> Result<Vec<BrokenLink>> {
+        let mut broken = Vec::new();
+        for page in crate::builder::Builder::collect_pages(&self.book.items) {
+            broken.extend(self.check_page(page)?);
+        }
+        Ok(broken)
+    }
+
+    fn check_page(&mut self, page: &PageInfo) -> Result<Vec<BrokenLink>> {
+        let output_file = self.output_dir.join(&page.output_filename);
+        let html = fs::read_to_string(&output_file)
+            .context(format!("Failed to read {}", output_file.display()))?;
+        let source = fs::read_to_string(&page.source_path).unwrap_or_default();
+
+        let mut broken = Vec::new();
+        for url in page_links(&html) {
+            if url.starts_with("http://") || url.starts_with("https://") {
+                self.external.push(ExternalLink {
+                    source_path: page.source_path.clone(),
+                    line: source_line(&source, &url),
+                    url,
+                });
+                continue;
+            }
+            if let Some(reason) = self.verify(page, &url)? {
+                broken.push(BrokenLink {
+                    source_path: page.source_path.clone(),
+                    line: source_line(&source, &url),
+                    url,
+                    reason,
+                });
+            }
+        }
+        Ok(broken)
+    }
+
+    /// Returns the reason a URL is broken, or None when it resolves
+    fn verify(&mut self, page: &PageInfo, url: &str) -> Result<Option<String>> {
+        if crate::html::is_external(url)
+            || url.starts_with("javascript:")
+            || url.starts_with("tel:")
+            || url.is_empty()
+            || url == "#"
+        {
+            return Ok(None);
+        }
+
+        let (path, fragment) = match url.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (url, None),
+        };
+        let path = path.split('?').next().unwrap_or_default();
+
+        let target = if path.is_empty() {
+            page.output_filename.clone()
+        } else {
+            let mut target = self.book.resolve_url(page, path);
+            if self.output_dir.join(&target).is_dir() {
+                target = format!("{}/index.html", target.trim_end_matches('/'))
+                    .trim_start_matches('/')
+                    .to_string();
+            }
+            target
+        };
+
+        if !self.output_dir.join(&target).is_file() {
+            return Ok(Some(format!("{} not found in output", target)));
+        }
+
+        if let Some(fragment) = fragment.filter(|f| !f.is_empty())
+            && target.ends_with(".html")
+        {
+            let ids = self.ids_of(&target)?;
+            if !ids.contains(fragment) && !ids.contains(&percent_decode(fragment)) {
+                return Ok(Some(format!(
+                    "no element with id \"{}\" in {}",
+                    fragment, target
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn ids_of(&mut self, target: &str) -> Result<&HashSet<String>> {
+        if !self.ids.contains_key(target) {
+            let path = self.output_dir.join(target);
+            let html =
+                fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+            let mut ids = HashSet::new();
+            for attr in ["id", "name"] {
+                for value in attribute_values(&html, attr) {
+                    ids.insert(percent_decode(&value));
+                    ids.insert(value);
+                }
+            }
+            self.ids.insert(target.to_string(), ids);
+        }
+        Ok(&self.ids[target])
+    }
+}
+
+/// href and src values between the content markers (from the content
+/// wrapper on when they are missing)
+fn page_links(html: &str) -> Vec<String> {
+    let body = match html.find(CONTENT_START) {
+        Some(start) => {
+            let body = &html[start + CONTENT_START.len()..];
+            body.find(CONTENT_END).map_or(body, |end| &body[..end])
+        }
+        None => html
+            .find("<div id=\"content-wrapper\">")
+            .map_or(html, |pos| &html[pos..]),
+    };
+    let mut links = attribute_values(body, "href");
+    links.extend(attribute_values(body, "src"));
+    links
+}
+
+fn attribute_values(html: &str, attr: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    crate::html::rewrite_attribute(html, attr, |value| {
+        values.push(value.replace("&amp;", "&"));
+        value.to_string()
+    });
+    values
+}
+
+/// First line of the markdown source mentioning the URL (or its path / fragment part)
+fn source_line(source: &str, url: &str) -> Option<usize> {
+    let (path, fragment) = url.split_once('#').unwrap_or((url, ""));
+    let fragment = (!fragment.is_empty()).then(|| format!("#{}", fragment));
+    let decoded = percent_decode(url);
+    let needles = [
+        Some(url),
+        Some(decoded.as_str()),
+        Some(path),
+        fragment.as_deref(),
+    ];
+
+    needles
+        .into_iter()
+        .flatten()
+        .filter(|needle| !needle.is_empty())
+        .find_map(|needle| source.lines().position(|line| line.contains(needle)))
+        .map(|index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::BookItem;
+    use crate::config::Config;
+
+    fn page(title: &str, output_filename: &str, source_path: PathBuf) -> PageInfo {
+        PageInfo {
+            title: title.to_string(),
+            source_path,
+            output_filename: output_filename.to_string(),
+            url: format!("/{}", output_filename),
+            sections: vec![],
+            word_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_check_links() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-check");
+        let output_dir = temp_dir.join("docs");
+        fs::create_dir_all(output_dir.join("guide")).unwrap();
+        fs::create_dir_all(temp_dir.join("src")).unwrap();
+
+        fs::write(
+            temp_dir.join("src/intro.md"),
+            "# Intro\n\nSee [setup](guide/setup.html#2-Install).\n\n[gone](missing.html)\n",
+        )
+        .unwrap();
+        fs::write(
+            output_dir.join("intro.html"),
+            concat!(
+                r#"<nav><a href="/broken-in-toc.html">x</a></nav>"#,
+                r#"<div id="content-wrapper"><h1 id="intro">Intro</h1>"#,
+                r#"<a href="guide/setup.html#2-Install">setup</a>"#,
+                r#"<a href="/guide/setup.html#2-Missing">bad anchor</a>"#,
+                r#"<a href="missing.html">gone</a>"#,
+                r##"<a href="#intro">self</a><a href="https://example.com/">ext</a>"##,
+                r#"<img src="logo.png"></div>"#
+            ),
+        )
+        .unwrap();
+        fs::write(
+            output_dir.join("guide/setup.html"),
+            r#"<div id="content-wrapper"><h2 id="2-Install">Install</h2></div>"#,
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(
+            "[book]\ntitle = \"Test\"\n[[pages]]\ntitle = \"Intro\"\npath = \"intro.md\"\n",
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![
+                BookItem::Page(page("Intro", "intro.html", temp_dir.join("src/intro.md"))),
+                BookItem::Page(page(
+                    "Setup",
+                    "guide/setup.html",
+                    temp_dir.join("src/guide/setup.md"),
+                )),
+            ],
+        };
+
+        let broken = LinkChecker::new(&book, &output_dir).check().unwrap();
+        let urls: Vec<&str> = broken.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["/guide/setup.html#2-Missing", "missing.html", "logo.png"]
+        );
+        assert_eq!(broken[1].line, Some(5));
+        assert_eq!(broken[0].line, None);
+        assert!(broken[0].reason.contains("2-Missing"));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_page_links_skip_chrome() {
+        let html = format!(
+            concat!(
+                r#"<div id="content-wrapper"><div class="page-controls">"#,
+                r#"<a href="/print.html">print</a>"#,
+                r#"<a href="https://git.example.com/docs/edit/main/src/intro.md">edit</a>"#,
+                r#"<a href="https://git.example.com/docs">source</a></div>"#,
+                r#"<div id="search-modal"><input id="search-input"></div>{}"#,
+                r#"<p><a href="https://example.com/">ext</a><img src="logo.png"></p>{}"#,
+                r#"<footer class="page-footer"><a href="/x.html">x</a></footer></div>"#
+            ),
+            CONTENT_START, CONTENT_END
+        );
+        assert_eq!(page_links(&html), vec!["https://example.com/", "logo.png"]);
+    }
+}
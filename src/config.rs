@@ -1,32 +1,64 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub book: BookConfig,
     #[serde(default)]
     pub build: BuildConfig,
     #[serde(default)]
     pub toc: TocConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub check: CheckConfig,
+    #[serde(default)]
+    pub lint: LintConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
     pub pages: Vec<PageConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct BookConfig {
     pub title: String,
     #[serde(default)]
-    #[allow(dead_code)]
     pub description: Option<String>,
     #[serde(default)]
-    #[allow(dead_code)]
     pub authors: Vec<String>,
     #[serde(default = "default_language")]
     pub language: String,
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Public URL of the site (e.g. "https://example.com")
+    /// Enables sitemap.xml, robots.txt and canonical links
+    #[serde(default)]
+    pub site_url: Option<String>,
+    /// Default image for OpenGraph/Twitter card previews
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Repository of the book sources; adds a "View source" link to the page controls
+    #[serde(default)]
+    pub repository_url: Option<String>,
+    /// "Edit this page" URL; `{path}` is replaced by the page source path
+    /// relative to the book root (e.g. "https://git.example.com/docs/edit/main/{path}")
+    #[serde(default)]
+    pub edit_url_template: Option<String>,
 }
 
+/// Themes defined in assets/themes.css
+pub const THEMES: &[&str] = &[
+    "light",
+    "dark",
+    "solarized-light",
+    "solarized-dark",
+    "kanagawa-light",
+    "kanagawa-dark",
+];
+
 fn default_theme() -> String {
     "light".to_string()
 }
@@ -35,7 +67,8 @@ fn default_language() -> String {
     "ja".to_string()
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct BuildConfig {
     #[serde(default = "default_src_dir")]
     pub src_dir: PathBuf,
@@ -43,9 +76,13 @@ pub struct BuildConfig {
     pub output_dir: PathBuf,
     #[serde(default = "default_base_path")]
     pub base_path: String,
+    /// Treat warnings as errors, like `--strict`
+    #[serde(default)]
+    pub strict: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct TocConfig {
     /// When to show H2 sections in TOC
     /// - "always": Show sections for all pages
@@ -60,6 +97,182 @@ pub struct TocConfig {
     pub foldlevel: u8,
 }
 
+/// Additional output formats generated by `unibook build`
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    /// `[output.epub]`: also package the book as EPUB
+    #[serde(default)]
+    pub epub: Option<EpubConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EpubConfig {
+    /// Output file name, relative to output_dir
+    #[serde(default = "default_epub_filename")]
+    pub filename: String,
+}
+
+fn default_epub_filename() -> String {
+    "book.epub".to_string()
+}
+
+impl Default for EpubConfig {
+    fn default() -> Self {
+        Self {
+            filename: default_epub_filename(),
+        }
+    }
+}
+
+/// `[check]`: options for `unibook check --external`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckConfig {
+    /// URLs treated as valid without a request (URL prefix or host name)
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// URLs always reported as broken (URL prefix or host name)
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Request timeout in seconds
+    #[serde(default = "default_check_timeout")]
+    pub timeout: u64,
+    /// Retries after a network error, 429 or 5xx response
+    #[serde(default = "default_check_retries")]
+    pub retries: u32,
+    /// Number of requests in flight
+    #[serde(default = "default_check_concurrency")]
+    pub concurrency: usize,
+    /// Minimum interval between requests to the same host, in milliseconds
+    #[serde(default = "default_check_host_interval")]
+    pub host_interval_ms: u64,
+    /// Cache of successful results, relative to the book directory
+    #[serde(default = "default_check_cache_file")]
+    pub cache_file: PathBuf,
+    /// How long a cached result stays valid, in hours
+    #[serde(default = "default_check_cache_ttl")]
+    pub cache_ttl_hours: u64,
+}
+
+fn default_check_timeout() -> u64 {
+    10
+}
+
+fn default_check_retries() -> u32 {
+    2
+}
+
+fn default_check_concurrency() -> usize {
+    8
+}
+
+fn default_check_host_interval() -> u64 {
+    500
+}
+
+fn default_check_cache_file() -> PathBuf {
+    PathBuf::from(".unibook-link-cache.json")
+}
+
+fn default_check_cache_ttl() -> u64 {
+    24
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            timeout: default_check_timeout(),
+            retries: default_check_retries(),
+            concurrency: default_check_concurrency(),
+            host_interval_ms: default_check_host_interval(),
+            cache_file: default_check_cache_file(),
+            cache_ttl_hours: default_check_cache_ttl(),
+        }
+    }
+}
+
+/// Severity of a lint rule; "error" makes `unibook lint` exit non-zero
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+/// `[lint]`: severity of each `unibook lint` rule
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// The first H1 of a page must match its title in book.toml
+    #[serde(default = "default_warning")]
+    pub first_h1_title: Severity,
+    /// Heading levels must not skip (e.g. H1 followed by H3)
+    #[serde(default = "default_warning")]
+    pub heading_increment: Severity,
+    /// H2 headings must be unique within a page (they become "2-" ids)
+    #[serde(default = "default_error")]
+    pub duplicate_h2: Severity,
+    /// `$` math delimiters must be balanced on each line
+    #[serde(default = "default_error")]
+    pub unbalanced_math: Severity,
+    #[serde(default = "default_warning")]
+    pub trailing_whitespace: Severity,
+    /// Fenced code blocks must name a language
+    #[serde(default = "default_warning")]
+    pub code_block_language: Severity,
+}
+
+fn default_warning() -> Severity {
+    Severity::Warning
+}
+
+fn default_error() -> Severity {
+    Severity::Error
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            first_h1_title: default_warning(),
+            heading_increment: default_warning(),
+            duplicate_h2: default_error(),
+            unbalanced_math: default_error(),
+            trailing_whitespace: default_warning(),
+            code_block_language: default_warning(),
+        }
+    }
+}
+
+/// `[search]`: tokenization of the search index
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchConfig {
+    /// Fold full-width ASCII and half-width katakana
+    #[serde(default = "default_true")]
+    pub normalize_width: bool,
+    /// Fold katakana into hiragana
+    #[serde(default)]
+    pub normalize_kana: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            normalize_width: true,
+            normalize_kana: false,
+        }
+    }
+}
+
 fn default_show_sections() -> String {
     "current".to_string()
 }
@@ -68,9 +281,13 @@ fn default_foldlevel() -> u8 {
     0
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct PageConfig {
-    pub title: String,
+    /// Page title. When omitted, taken from front matter `title`, the first H1,
+    /// or the file name. Required for parts
+    #[serde(default)]
+    pub title: Option<String>,
     /// Path to the markdown file. If None, this is a part (separator/heading only)
     pub path: Option<String>,
     /// Child pages under this part (only valid when path is None)
@@ -79,17 +296,26 @@ pub struct PageConfig {
     /// - Some([...]): explicit children
     #[serde(default)]
     pub items: Option<Vec<PageItem>>,
+    /// Deprecated and ignored: the level of a part follows its nesting depth
+    #[serde(default, skip_serializing)]
+    pub level: Option<u8>,
 }
 
 /// A page item that can be nested under a part
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct PageItem {
-    pub title: String,
+    /// Same fallback as `PageConfig.title`
+    #[serde(default)]
+    pub title: Option<String>,
     #[serde(default)]
     pub path: String,
     /// Child items under this part (only valid when path is empty)
     #[serde(default)]
     pub items: Option<Vec<PageItem>>,
+    /// Deprecated and ignored, as in `PageConfig`
+    #[serde(default, skip_serializing)]
+    pub level: Option<u8>,
 }
 
 fn default_src_dir() -> PathBuf {
@@ -110,6 +336,7 @@ impl Default for BuildConfig {
             src_dir: default_src_dir(),
             output_dir: default_output_dir(),
             base_path: default_base_path(),
+            strict: false,
         }
     }
 }
@@ -127,11 +354,48 @@ impl Config {
     pub fn from_file(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path)
             .context(format!("Failed to read config file: {}", path.display()))?;
-        let config: Config = toml::from_str(&contents).context("Failed to parse book.toml")?;
+        let config: Config = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("{}", with_suggestion(&e.to_string())))
+            .context("Failed to parse book.toml")?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Settings that are accepted but probably mistakes; errors under `--strict`
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !THEMES.contains(&self.book.theme.as_str()) {
+            warnings.push(format!(
+                "unknown theme `{}` (expected one of {})",
+                self.book.theme,
+                THEMES.join(", ")
+            ));
+        }
+        for page in &self.pages {
+            if page.level.is_some() {
+                warnings.push(deprecated_level(
+                    page.title.as_deref(),
+                    page.path.as_deref(),
+                ));
+            }
+            if let Some(items) = &page.items {
+                Self::item_warnings(items, &mut warnings);
+            }
+        }
+        warnings
+    }
+
+    fn item_warnings(items: &[PageItem], warnings: &mut Vec<String>) {
+        for item in items {
+            if item.level.is_some() {
+                warnings.push(deprecated_level(item.title.as_deref(), Some(&item.path)));
+            }
+            if let Some(children) = &item.items {
+                Self::item_warnings(children, warnings);
+            }
+        }
+    }
+
     fn validate(&self) -> Result<()> {
         if self.book.title.is_empty() {
             anyhow::bail!("Book title cannot be empty");
@@ -139,15 +403,112 @@ impl Config {
         if self.pages.is_empty() {
             anyhow::bail!("No pages defined in book.toml");
         }
-        // Check for duplicate titles
+        // Parts have no file to take a title from; explicit page titles must be
+        // unique (inferred titles are checked once resolved, in Book::from_config)
         let mut titles = std::collections::HashSet::new();
         for page in &self.pages {
-            if !titles.insert(&page.title) {
-                anyhow::bail!("Duplicate page title: {}", page.title);
+            if page.path.is_none() && page.title.is_none() {
+                anyhow::bail!("A part (page without path) must have a title");
+            }
+            if let Some(path) = &page.path {
+                check_generated_output(path)?;
+            }
+            if page.path.is_some()
+                && let Some(title) = &page.title
+                && !titles.insert(title)
+            {
+                anyhow::bail!("Duplicate page title: {}", title);
+            }
+            if let Some(items) = &page.items {
+                Self::validate_items(items, &mut titles)?;
             }
         }
         Ok(())
     }
+
+    fn validate_items<'a>(
+        items: &'a [PageItem],
+        titles: &mut std::collections::HashSet<&'a String>,
+    ) -> Result<()> {
+        for item in items {
+            if item.path.is_empty() && item.title.is_none() {
+                anyhow::bail!("A nested part (item without path) must have a title");
+            }
+            if !item.path.is_empty() {
+                check_generated_output(&item.path)?;
+            }
+            if !item.path.is_empty()
+                && let Some(title) = &item.title
+                && !titles.insert(title)
+            {
+                anyhow::bail!("Duplicate page title: {}", title);
+            }
+            if let Some(children) = &item.items {
+                Self::validate_items(children, titles)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pages unibook generates in output_dir
+const GENERATED_PAGES: &[&str] = &["404.html", "print.html", "single.html"];
+
+/// A page must not be written where a generated page would overwrite it
+fn check_generated_output(path: &str) -> Result<()> {
+    if let Ok(output_filename) = crate::book::Book::source_to_html_filename(path)
+        && GENERATED_PAGES.contains(&output_filename.as_str())
+    {
+        anyhow::bail!(
+            "Page {} would be overwritten by the generated {} (rename or move the file)",
+            path,
+            output_filename
+        );
+    }
+    Ok(())
+}
+
+fn deprecated_level(title: Option<&str>, path: Option<&str>) -> String {
+    format!(
+        "`level` on [[pages]] \"{}\" is ignored (the level of a part follows its nesting depth)",
+        title.or(path).unwrap_or_default()
+    )
+}
+
+/// Append "did you mean" to serde's "unknown field `x`, expected one of `a`, `b`" errors
+fn with_suggestion(message: &str) -> String {
+    let suggestion = message.lines().find_map(|line| {
+        let rest = line.strip_prefix("unknown field `")?;
+        let (unknown, expected) = rest.split_once('`')?;
+        expected
+            .split('`')
+            .skip(1)
+            .step_by(2)
+            .map(|field| (edit_distance(unknown, field), field))
+            .filter(|(distance, field)| *distance <= field.len().max(unknown.len()) / 3 + 1)
+            .min()
+            .map(|(_, field)| field.to_string())
+    });
+    match suggestion {
+        Some(field) => format!("{}\nhelp: did you mean `{}`?", message.trim_end(), field),
+        None => message.to_string(),
+    }
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
 }
 
 #[cfg(test)]
@@ -182,7 +543,7 @@ path = "page2.md"
         assert_eq!(config.build.src_dir, PathBuf::from("source"));
         assert_eq!(config.build.output_dir, PathBuf::from("output"));
         assert_eq!(config.pages.len(), 2);
-        assert_eq!(config.pages[0].title, "Page 1");
+        assert_eq!(config.pages[0].title.as_deref(), Some("Page 1"));
         assert_eq!(config.pages[0].path, Some("page1.md".to_string()));
     }
 
@@ -226,9 +587,17 @@ path = "page1.md"
                 authors: vec![],
                 language: "en".to_string(),
                 theme: "light".to_string(),
+                site_url: None,
+                image: None,
+                repository_url: None,
+                edit_url_template: None,
             },
             build: BuildConfig::default(),
             toc: TocConfig::default(),
+            output: OutputConfig::default(),
+            check: CheckConfig::default(),
+            lint: LintConfig::default(),
+            search: SearchConfig::default(),
             pages: vec![],
         };
         assert!(config.validate().is_err());
@@ -252,6 +621,139 @@ path = "page2.md"
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_config_validation_nested_titles() {
+        let toml_content = r#"
+[book]
+title = "Test Book"
+
+[[pages]]
+title = "Part 1"
+items = [
+    { title = "Setup", path = "setup.md" },
+    { title = "Nested", items = [{ title = "Setup", path = "other.md" }] },
+]
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(config.validate().is_err());
+
+        // A part may share its title with a page
+        let toml_content = r#"
+[book]
+title = "Test Book"
+
+[[pages]]
+title = "Setup"
+items = [{ title = "Setup", path = "setup.md" }]
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(config.validate().is_ok());
+
+        // Pages may omit the title, parts may not
+        let toml_content = r#"
+[book]
+title = "Test Book"
+
+[[pages]]
+path = "intro.md"
+
+[[pages]]
+items = []
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(config.pages[0].title.is_none());
+        let error = config.validate().unwrap_err();
+        assert!(error.to_string().contains("must have a title"));
+    }
+
+    #[test]
+    fn test_config_validation_generated_pages() {
+        for path in ["404.md", "print.md", "single.md"] {
+            let config: Config = toml::from_str(&format!(
+                "[book]\ntitle = \"Test\"\n[[pages]]\ntitle = \"Part\"\nitems = [{{ path = \"{}\" }}]\n",
+                path
+            ))
+            .unwrap();
+            let error = config.validate().unwrap_err();
+            assert!(error.to_string().contains("overwritten by the generated"));
+        }
+
+        // Only the output_dir root is generated
+        let config: Config =
+            toml::from_str("[book]\ntitle = \"Test\"\n[[pages]]\npath = \"guide/404.md\"\n")
+                .unwrap();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_unknown_fields() {
+        let toml_content = r#"
+[book]
+title = "Test Book"
+
+[[pages]]
+titel = "Page 1"
+path = "page1.md"
+"#;
+        let error = toml::from_str::<Config>(toml_content).unwrap_err();
+        let message = with_suggestion(&error.to_string());
+        assert!(message.contains("unknown field `titel`"));
+        assert!(message.ends_with("help: did you mean `title`?"));
+
+        let error = toml::from_str::<Config>(
+            "[book]\ntitle = \"Test\"\n[build]\nsrcdir = \"src\"\n[[pages]]\npath = \"a.md\"\n",
+        )
+        .unwrap_err();
+        assert!(with_suggestion(&error.to_string()).ends_with("did you mean `src_dir`?"));
+
+        // Nothing close enough: no suggestion
+        assert_eq!(
+            with_suggestion("unknown field `colour`, expected `title` or `path`"),
+            "unknown field `colour`, expected `title` or `path`"
+        );
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_config_warnings() {
+        let toml_content = r#"
+[book]
+title = "Test Book"
+theme = "solarized"
+
+[build]
+strict = true
+
+[[pages]]
+title = "Part 1"
+level = 1
+items = [{ title = "Setup", path = "setup.md", level = 2 }]
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert!(config.build.strict);
+        let warnings = config.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("unknown theme `solarized`"));
+        assert!(warnings[1].contains("\"Part 1\" is ignored"));
+        assert!(warnings[2].contains("\"Setup\" is ignored"));
+    }
+
+    #[test]
+    fn test_config_output_epub() {
+        let toml_content = r#"
+[book]
+title = "Test Book"
+
+[output.epub]
+
+[[pages]]
+title = "Page 1"
+path = "page1.md"
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.output.epub.unwrap().filename, "book.epub");
+    }
+
     #[test]
     fn test_config_from_file() {
         let temp_dir = std::env::temp_dir();
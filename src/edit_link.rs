@@ -0,0 +1,118 @@
+use crate::book::{Book, PageInfo};
+use crate::meta::FrontMatter;
+use crate::toc::attr_escape;
+use std::path::Path;
+
+pub struct EditLink;
+
+impl EditLink {
+    /// Page-control links for a page: "Edit this page" and "View source"
+    /// A page opts out with `edit: false` in its front matter
+    pub fn controls_html(book: &Book, page: &PageInfo, base_dir: &Path) -> String {
+        let content = std::fs::read_to_string(&page.source_path).unwrap_or_default();
+        let (front_matter, _) = FrontMatter::parse(&content);
+        if front_matter.get("edit") == Some("false") {
+            return String::new();
+        }
+
+        let mut html = String::new();
+        if let Some(url) = Self::edit_url(book, page, base_dir) {
+            html.push_str(&format!(
+                r#"<a id="edit-link" class="page-control-link" href="{}" title="Edit this page" target="_blank" rel="noopener">
+  <svg width="16" height="16" viewBox="0 0 16 16" fill="currentColor">
+    <path d="M12.1 1.3a1 1 0 0 1 1.4 0l1.2 1.2a1 1 0 0 1 0 1.4L6 12.6 2.5 13.5l.9-3.5 8.7-8.7zM1 15h14v1H1v-1z"/>
+  </svg>
+</a>
+"#,
+                attr_escape(&url)
+            ));
+        }
+        if let Some(repository_url) = &book.config.book.repository_url {
+            html.push_str(&format!(
+                r#"<a id="source-link" class="page-control-link" href="{}" title="View source" target="_blank" rel="noopener">
+  <svg width="16" height="16" viewBox="0 0 16 16" fill="currentColor">
+    <path d="M5.7 3.3 1 8l4.7 4.7 1-1L3 8l3.7-3.7-1-1zm4.6 0-1 1L13 8l-3.7 3.7 1 1L15 8l-4.7-4.7z"/>
+  </svg>
+</a>
+"#,
+                attr_escape(repository_url)
+            ));
+        }
+        html
+    }
+
+    /// edit_url_template with `{path}` replaced by the source path relative to the book root
+    pub fn edit_url(book: &Book, page: &PageInfo, base_dir: &Path) -> Option<String> {
+        let template = book.config.book.edit_url_template.as_ref()?;
+        let relative = page.source_path.strip_prefix(base_dir).ok()?;
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().replace(' ', "%20"))
+            .collect::<Vec<_>>()
+            .join("/");
+        Some(template.replace("{path}", &path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_controls_html() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-edit-link");
+        std::fs::create_dir_all(temp_dir.join("src/guide")).unwrap();
+        std::fs::write(temp_dir.join("src/guide/setup.md"), "# Setup\n").unwrap();
+        std::fs::write(
+            temp_dir.join("src/draft.md"),
+            "---\nedit: false\n---\n# Draft\n",
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+[book]
+title = "Test"
+repository_url = "https://git.example.com/docs"
+edit_url_template = "https://git.example.com/docs/edit/main/{path}"
+
+[[pages]]
+path = "guide/setup.md"
+"#,
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![],
+        };
+        let page = |path: &str| PageInfo {
+            title: "Page".to_string(),
+            source_path: temp_dir.join("src").join(path),
+            output_filename: path.replace(".md", ".html"),
+            url: String::new(),
+            sections: vec![],
+            word_count: 0,
+        };
+
+        let html = EditLink::controls_html(&book, &page("guide/setup.md"), &temp_dir);
+        assert!(html.contains(
+            r#"href="https://git.example.com/docs/edit/main/src/guide/setup.md" title="Edit this page""#
+        ));
+        assert!(html.contains(r#"href="https://git.example.com/docs" title="View source""#));
+
+        // Per-page opt-out
+        assert_eq!(
+            EditLink::controls_html(&book, &page("draft.md"), &temp_dir),
+            ""
+        );
+
+        // Outside the book root: no edit link
+        let mut outside = page("guide/setup.md");
+        outside.source_path = PathBuf::from("/elsewhere/setup.md");
+        assert_eq!(EditLink::edit_url(&book, &outside, &temp_dir), None);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+}
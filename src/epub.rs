@@ -0,0 +1,438 @@
+use crate::book::{Book, BookItem, PageInfo};
+use crate::html::{is_external, media_type, rewrite_attribute};
+use crate::toc::attr_escape;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Elements that must be self-closed in XHTML
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+pub struct EpubWriter<'a> {
+    book: &'a Book,
+    base_dir: &'a Path,
+}
+
+impl<'a> EpubWriter<'a> {
+    pub fn new(book: &'a Book, base_dir: &'a Path) -> Self {
+        Self { book, base_dir }
+    }
+
+    /// Package rendered page fragments into an EPUB 3 file
+    /// `fragments` maps output_filename to the page's rendered HTML (body only)
+    pub fn write(&self, fragments: &HashMap<String, String>, output_path: &Path) -> Result<()> {
+        let pages = crate::builder::Builder::collect_pages(&self.book.items);
+
+        // Content documents and the images they reference
+        let mut documents = Vec::new();
+        let mut images: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for &page in &pages {
+            let Some(fragment) = fragments.get(&page.output_filename) else {
+                continue;
+            };
+            let fragment = self.rewrite_fragment(fragment, page, &pages, &mut images);
+            documents.push((page, self.page_document(page, &fragment)));
+        }
+
+        let file = fs::File::create(output_path)
+            .context(format!("Failed to create {}", output_path.display()))?;
+        let mut zip = ZipWriter::new(file);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        // mimetype must be the first entry and stored uncompressed
+        zip.start_file("mimetype", stored)?;
+        zip.write_all(b"application/epub+zip")?;
+
+        zip.start_file("META-INF/container.xml", deflated)?;
+        zip.write_all(CONTAINER_XML.as_bytes())?;
+
+        zip.start_file("OEBPS/content.opf", deflated)?;
+        zip.write_all(self.package_document(&documents, &images).as_bytes())?;
+
+        zip.start_file("OEBPS/nav.xhtml", deflated)?;
+        zip.write_all(self.nav_document().as_bytes())?;
+
+        zip.start_file("OEBPS/style.css", deflated)?;
+        zip.write_all(crate::search_assets::SearchAssets::epub_css().as_bytes())?;
+
+        for (page, document) in &documents {
+            zip.start_file(format!("OEBPS/{}", page.output_filename), deflated)?;
+            zip.write_all(document.as_bytes())?;
+        }
+
+        for (path, source) in &images {
+            let data = fs::read(source).context(format!("Failed to read {}", source.display()))?;
+            zip.start_file(format!("OEBPS/{}", path), stored)?;
+            zip.write_all(&data)?;
+        }
+
+        zip.finish().context("Failed to write EPUB")?;
+        Ok(())
+    }
+
+    /// Make a fragment valid XHTML and fix up links and image paths
+    /// Images are registered in `images` (path in the EPUB -> file on disk)
+    fn rewrite_fragment(
+        &self,
+        fragment: &str,
+        page: &PageInfo,
+        pages: &[&PageInfo],
+        images: &mut BTreeMap<String, PathBuf>,
+    ) -> String {
+        let to_root = "../".repeat(page.output_filename.matches('/').count());
+
+        let html = rewrite_attribute(fragment, "href", |href| {
+            if href.starts_with('#') || is_external(href) {
+                return href.to_string();
+            }
+            let (path, fragment) = match href.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (href, None),
+            };
+            let mut target = self.book.resolve_url(page, path);
+            if let Some(stem) = target.strip_suffix(".md") {
+                target = format!("{}.html", stem);
+            }
+            if !pages.iter().any(|p| p.output_filename == target) {
+                return href.to_string();
+            }
+            let mut url = format!("{}{}", to_root, target);
+            if let Some(fragment) = fragment {
+                url.push('#');
+                url.push_str(fragment);
+            }
+            url
+        });
+
+        let html = rewrite_attribute(&html, "src", |src| {
+            if is_external(src) {
+                return src.to_string();
+            }
+            let path = self.book.resolve_url(page, src);
+            match self
+                .book
+                .find_asset(self.base_dir, page, src)
+                .filter(|_| media_type(&path).is_some())
+            {
+                Some(source) => {
+                    images.insert(path.clone(), source);
+                    format!("{}{}", to_root, path)
+                }
+                None => {
+                    crate::log::warning!(
+                        "image not found for EPUB: {} (in {})",
+                        src,
+                        page.output_filename
+                    );
+                    src.to_string()
+                }
+            }
+        });
+
+        to_xhtml(&html)
+    }
+
+    fn page_document(&self, page: &PageInfo, body: &str) -> String {
+        let to_root = "../".repeat(page.output_filename.matches('/').count());
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{lang}" lang="{lang}">
+<head>
+  <meta charset="UTF-8"/>
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="{to_root}style.css"/>
+</head>
+<body>
+{body}
+</body>
+</html>
+"#,
+            lang = attr_escape(&self.book.config.book.language),
+            title = attr_escape(&page.title),
+            to_root = to_root,
+            body = body
+        )
+    }
+
+    /// EPUB 3 navigation document generated from the BookItem tree
+    fn nav_document(&self) -> String {
+        let mut list = String::new();
+        render_nav_items(&mut list, &self.book.items, 2);
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{lang}" lang="{lang}">
+<head>
+  <meta charset="UTF-8"/>
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+  <nav epub:type="toc" id="toc">
+    <h1>{title}</h1>
+    <ol>
+{list}    </ol>
+  </nav>
+</body>
+</html>
+"#,
+            lang = attr_escape(&self.book.config.book.language),
+            title = attr_escape(&self.book.config.book.title),
+            list = list
+        )
+    }
+
+    fn package_document(
+        &self,
+        documents: &[(&PageInfo, String)],
+        images: &BTreeMap<String, PathBuf>,
+    ) -> String {
+        let config = &self.book.config.book;
+        let identifier = self
+            .book
+            .absolute_url("")
+            .unwrap_or_else(|| format!("urn:unibook:{}", config.title));
+        let modified = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut metadata = String::new();
+        metadata.push_str(&format!(
+            "    <dc:identifier id=\"book-id\">{}</dc:identifier>\n",
+            attr_escape(&identifier)
+        ));
+        metadata.push_str(&format!(
+            "    <dc:title>{}</dc:title>\n",
+            attr_escape(&config.title)
+        ));
+        metadata.push_str(&format!(
+            "    <dc:language>{}</dc:language>\n",
+            attr_escape(&config.language)
+        ));
+        for author in &config.authors {
+            metadata.push_str(&format!(
+                "    <dc:creator>{}</dc:creator>\n",
+                attr_escape(author)
+            ));
+        }
+        if let Some(description) = &config.description {
+            metadata.push_str(&format!(
+                "    <dc:description>{}</dc:description>\n",
+                attr_escape(description)
+            ));
+        }
+        metadata.push_str(&format!(
+            "    <meta property=\"dcterms:modified\">{}</meta>\n",
+            crate::sitemap::format_datetime(modified)
+        ));
+
+        let mut manifest = String::new();
+        manifest.push_str(
+            "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n",
+        );
+        manifest.push_str("    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n");
+        let mut spine = String::new();
+        for (i, (page, _)) in documents.iter().enumerate() {
+            manifest.push_str(&format!(
+                "    <item id=\"page-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+                i,
+                attr_escape(&page.output_filename)
+            ));
+            spine.push_str(&format!("    <itemref idref=\"page-{}\"/>\n", i));
+        }
+        for (i, path) in images.keys().enumerate() {
+            manifest.push_str(&format!(
+                "    <item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+                i,
+                attr_escape(path),
+                media_type(path).unwrap_or("application/octet-stream")
+            ));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{lang}">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+{metadata}  </metadata>
+  <manifest>
+{manifest}  </manifest>
+  <spine>
+{spine}  </spine>
+</package>
+"#,
+            lang = attr_escape(&config.language),
+            metadata = metadata,
+            manifest = manifest,
+            spine = spine
+        )
+    }
+}
+
+fn render_nav_items(html: &mut String, items: &[BookItem], depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+    for item in items {
+        match item {
+            BookItem::Part { title, children } => {
+                // A nav <span> must be followed by a non-empty list
+                if children.is_empty() {
+                    continue;
+                }
+                html.push_str(&format!(
+                    "{}<li><span>{}</span>\n{}  <ol>\n",
+                    indent,
+                    attr_escape(title),
+                    indent
+                ));
+                render_nav_items(html, children, depth + 2);
+                html.push_str(&format!("{}  </ol>\n{}</li>\n", indent, indent));
+            }
+            BookItem::Page(page) => {
+                html.push_str(&format!(
+                    "{}<li><a href=\"{}\">{}</a></li>\n",
+                    indent,
+                    attr_escape(&page.output_filename),
+                    attr_escape(&page.title)
+                ));
+            }
+        }
+    }
+}
+
+/// Entities predefined in XML, which XHTML may keep
+const XML_ENTITIES: &[&str] = &["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"];
+
+/// Convert HTML to XHTML: self-close void elements and replace named entities
+/// that are not predefined in XML
+fn to_xhtml(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(pos) = rest.find('<') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..=end];
+        let name: String = tag[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if VOID_ELEMENTS.contains(&name.as_str()) && !tag.ends_with("/>") {
+            result.push_str(&tag[..tag.len() - 1]);
+            result.push_str("/>");
+        } else {
+            result.push_str(tag);
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    numeric_entities(&result)
+}
+
+/// Replace HTML named entities ("&nbsp;", "&rarr;", ...) with numeric
+/// character references; XML entities and unknown names are kept
+fn numeric_entities(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(pos) = rest.find('&') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let name_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len() - 1);
+        let entity = rest[1 + name_len..]
+            .starts_with(';')
+            .then(|| &rest[..name_len + 2])
+            .and_then(|reference| entities::ENTITIES.iter().find(|e| e.entity == reference))
+            .filter(|entity| !XML_ENTITIES.contains(&entity.entity));
+        match entity {
+            Some(entity) => {
+                for c in entity.characters.chars() {
+                    result.push_str(&format!("&#{};", c as u32));
+                }
+                rest = &rest[entity.entity.len()..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_xhtml() {
+        assert_eq!(
+            to_xhtml(r#"<p>a<br>b<img src="x.png" alt="x"><hr/></p>"#),
+            r#"<p>a<br/>b<img src="x.png" alt="x"/><hr/></p>"#
+        );
+        assert_eq!(to_xhtml("a&nbsp;b"), "a&#160;b");
+        assert_eq!(
+            to_xhtml("a &rarr; b &times; &laquo;c&raquo; &NotEqualTilde;"),
+            "a &#8594; b &#215; &#171;c&#187; &#8770;&#824;"
+        );
+        // XML entities, numeric references and unknown names are kept
+        assert_eq!(
+            to_xhtml("&amp;&lt;&#8594;&unknown; & &é x&"),
+            "&amp;&lt;&#8594;&unknown; & &é x&"
+        );
+        // Non-void elements are left untouched
+        assert_eq!(to_xhtml("<bdi>x</bdi>"), "<bdi>x</bdi>");
+    }
+
+    #[test]
+    fn test_render_nav_items_skips_empty_parts() {
+        let items = vec![
+            BookItem::Part {
+                title: "Empty".to_string(),
+                children: vec![],
+            },
+            BookItem::Part {
+                title: "Part 1".to_string(),
+                children: vec![BookItem::Page(PageInfo {
+                    title: "Intro".to_string(),
+                    source_path: PathBuf::from("src/intro.md"),
+                    output_filename: "intro.html".to_string(),
+                    url: "/intro.html".to_string(),
+                    sections: vec![],
+                    word_count: 0,
+                })],
+            },
+        ];
+        let mut html = String::new();
+        render_nav_items(&mut html, &items, 0);
+
+        assert!(!html.contains("Empty"));
+        assert!(html.contains("<li><span>Part 1</span>"));
+        assert!(html.contains("<li><a href=\"intro.html\">Intro</a></li>"));
+    }
+}
@@ -0,0 +1,318 @@
+use crate::check::BrokenLink;
+use crate::config::CheckConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// An http(s) URL found in a rendered page
+#[derive(Debug, Clone)]
+pub struct ExternalLink {
+    pub source_path: PathBuf,
+    pub line: Option<usize>,
+    pub url: String,
+}
+
+/// Successful results of previous runs: URL -> UNIX time of the check
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LinkCache {
+    checked: HashMap<String, u64>,
+}
+
+/// Checks external URLs concurrently, with per-host rate limiting, retries
+/// and a cache of successful results
+pub struct ExternalChecker<'a> {
+    config: &'a CheckConfig,
+    cache_path: PathBuf,
+}
+
+impl<'a> ExternalChecker<'a> {
+    pub fn new(config: &'a CheckConfig, base_dir: &Path) -> Self {
+        Self {
+            config,
+            cache_path: base_dir.join(&config.cache_file),
+        }
+    }
+
+    pub fn check(&self, links: &[ExternalLink]) -> Result<Vec<BrokenLink>> {
+        let now = unix_time();
+        let ttl = self.config.cache_ttl_hours * 3600;
+        let mut cache = self.load_cache();
+        cache
+            .checked
+            .retain(|_, checked_at| now.saturating_sub(*checked_at) < ttl);
+
+        // Unique URLs without fragments, minus allowed, denied and cached ones
+        let mut failures: HashMap<String, String> = HashMap::new();
+        let mut pending = Vec::new();
+        for link in links {
+            let url = strip_fragment(&link.url);
+            if pending.contains(&url) || failures.contains_key(&url) {
+                continue;
+            }
+            if matches_any(&url, &self.config.deny) {
+                failures.insert(url, "denied by [check] deny".to_string());
+            } else if !matches_any(&url, &self.config.allow) && !cache.checked.contains_key(&url) {
+                pending.push(url);
+            }
+        }
+
+        if !pending.is_empty() {
+            crate::log::info!("Checking {} external link(s)...", pending.len());
+        }
+        for (url, result) in self.request_all(pending) {
+            match result {
+                Ok(()) => {
+                    cache.checked.insert(url, now);
+                }
+                Err(reason) => {
+                    failures.insert(url, reason);
+                }
+            }
+        }
+        self.save_cache(&cache)?;
+
+        Ok(links
+            .iter()
+            .filter_map(|link| {
+                let reason = failures.get(&strip_fragment(&link.url))?;
+                Some(BrokenLink {
+                    source_path: link.source_path.clone(),
+                    line: link.line,
+                    url: link.url.clone(),
+                    reason: reason.clone(),
+                })
+            })
+            .collect())
+    }
+
+    /// Request every URL from `concurrency` worker threads
+    fn request_all(&self, urls: Vec<String>) -> Vec<(String, Result<(), String>)> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(self.config.timeout))
+            .user_agent(concat!("unibook/", env!("CARGO_PKG_VERSION")))
+            .build();
+        let queue = Mutex::new(VecDeque::from(urls));
+        let results = Mutex::new(Vec::new());
+        let limiter = HostLimiter::new(Duration::from_millis(self.config.host_interval_ms));
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.config.concurrency.max(1) {
+                scope.spawn(|| {
+                    loop {
+                        let Some(url) = queue.lock().unwrap().pop_front() else {
+                            break;
+                        };
+                        let result = self.request(&agent, &limiter, &url);
+                        results.lock().unwrap().push((url, result));
+                    }
+                });
+            }
+        });
+
+        results.into_inner().unwrap()
+    }
+
+    /// HEAD, falling back to GET when the server rejects HEAD; retries transient failures
+    fn request(&self, agent: &ureq::Agent, limiter: &HostLimiter, url: &str) -> Result<(), String> {
+        let mut attempt = 0;
+        loop {
+            limiter.wait(host(url));
+            let mut response = agent.head(url).call();
+            if let Err(ureq::Error::Status(405 | 403 | 501, _)) = response {
+                limiter.wait(host(url));
+                response = agent.get(url).call();
+            }
+
+            let (reason, transient) = match response {
+                Ok(_) => return Ok(()),
+                Err(ureq::Error::Status(code, _)) => {
+                    (format!("HTTP {}", code), code == 429 || code >= 500)
+                }
+                Err(ureq::Error::Transport(error)) => (error.to_string(), true),
+            };
+            if !transient || attempt >= self.config.retries {
+                return Err(reason);
+            }
+            attempt += 1;
+            std::thread::sleep(Duration::from_millis(
+                self.config.host_interval_ms.max(100) * attempt as u64,
+            ));
+        }
+    }
+
+    fn load_cache(&self) -> LinkCache {
+        fs::read_to_string(&self.cache_path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_cache(&self, cache: &LinkCache) -> Result<()> {
+        let json = serde_json::to_string_pretty(cache).context("Failed to serialize link cache")?;
+        fs::write(&self.cache_path, json).context(format!(
+            "Failed to write link cache: {}",
+            self.cache_path.display()
+        ))
+    }
+}
+
+/// Spaces out requests to the same host by a minimum interval
+struct HostLimiter {
+    interval: Duration,
+    next: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserve the next slot for `host` and sleep until it starts
+    fn wait(&self, host: &str) {
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let slot = next.get(host).map_or(now, |&t| t.max(now));
+            next.insert(host.to_string(), slot + self.interval);
+            slot
+        };
+        let now = Instant::now();
+        if slot > now {
+            std::thread::sleep(slot - now);
+        }
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn strip_fragment(url: &str) -> String {
+    url.split('#').next().unwrap_or_default().to_string()
+}
+
+/// "https://user@example.com:8080/a" -> "example.com"
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+    authority.split(':').next().unwrap_or_default()
+}
+
+/// A pattern matches URLs starting with it, or URLs on that host (and its subdomains)
+fn matches_any(url: &str, patterns: &[String]) -> bool {
+    let host = host(url);
+    patterns.iter().any(|pattern| {
+        url.starts_with(pattern.as_str())
+            || host == pattern
+            || host.ends_with(&format!(".{}", pattern))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Local HTTP stand-in: /ok, /missing (404), /no-head (405 on HEAD), /flaky (500 once)
+    fn start_server() -> (String, Arc<AtomicUsize>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            let mut flaky_failed = false;
+            for request in server.incoming_requests() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let is_head = *request.method() == tiny_http::Method::Head;
+                let status = match request.url() {
+                    "/ok" => 200,
+                    "/no-head" if is_head => 405,
+                    "/no-head" => 200,
+                    "/flaky" if !flaky_failed => {
+                        flaky_failed = true;
+                        500
+                    }
+                    "/flaky" => 200,
+                    _ => 404,
+                };
+                let _ = request.respond(tiny_http::Response::empty(status));
+            }
+        });
+
+        (format!("http://127.0.0.1:{}", port), requests)
+    }
+
+    fn link(url: String, line: usize) -> ExternalLink {
+        ExternalLink {
+            source_path: PathBuf::from("src/intro.md"),
+            line: Some(line),
+            url,
+        }
+    }
+
+    #[test]
+    fn test_external_check_and_cache() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-external");
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::remove_file(temp_dir.join(".unibook-link-cache.json")).ok();
+
+        let (base, requests) = start_server();
+        let config = CheckConfig {
+            host_interval_ms: 10,
+            deny: vec![format!("{}/denied", base)],
+            allow: vec!["example.com".to_string()],
+            ..CheckConfig::default()
+        };
+        let links = vec![
+            link(format!("{}/ok#intro", base), 1),
+            link(format!("{}/missing", base), 2),
+            link(format!("{}/no-head", base), 3),
+            link(format!("{}/flaky", base), 4),
+            link(format!("{}/denied/page", base), 5),
+            link("https://www.example.com/never-requested".to_string(), 6),
+            link(format!("{}/ok", base), 7),
+        ];
+
+        let checker = ExternalChecker::new(&config, &temp_dir);
+        let broken = checker.check(&links).unwrap();
+        let lines: Vec<Option<usize>> = broken.iter().map(|b| b.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(5)]);
+        assert_eq!(broken[0].reason, "HTTP 404");
+
+        // Successful URLs are cached; only the 404 is requested again
+        let before = requests.load(Ordering::SeqCst);
+        let broken = checker.check(&links).unwrap();
+        assert_eq!(broken.len(), 2);
+        assert_eq!(requests.load(Ordering::SeqCst) - before, 1);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_host_and_patterns() {
+        assert_eq!(
+            host("https://user@docs.example.com:8080/a?b"),
+            "docs.example.com"
+        );
+        let patterns = vec![
+            "example.com".to_string(),
+            "https://x.org/private".to_string(),
+        ];
+        assert!(matches_any("https://docs.example.com/a", &patterns));
+        assert!(matches_any("https://x.org/private/1", &patterns));
+        assert!(!matches_any("https://x.org/public", &patterns));
+        assert!(!matches_any("https://notexample.com/", &patterns));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::toc::attr_escape;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// When a page was last changed and by whom
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageHistory {
+    /// YYYY-MM-DD of the last commit (or file mtime outside git)
+    pub last_updated: Option<String>,
+    /// Commit authors, most recent first (empty outside git)
+    pub contributors: Vec<String>,
+}
+
+/// Per-build cache of `git log` results, keyed by source path
+#[derive(Debug, Default)]
+pub struct History {
+    pages: RefCell<HashMap<PathBuf, PageHistory>>,
+    /// Skip git and mtime lookups (every page gets an empty history)
+    disabled: bool,
+}
+
+impl History {
+    /// For commands that never show the history, e.g. `unibook search`
+    pub fn disabled() -> Self {
+        Self {
+            disabled: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn page(&self, source_path: &Path) -> PageHistory {
+        if self.disabled {
+            return PageHistory::default();
+        }
+        if let Some(history) = self.pages.borrow().get(source_path) {
+            return history.clone();
+        }
+        let history = git_history(source_path).unwrap_or_else(|| PageHistory {
+            last_updated: mtime_date(source_path),
+            contributors: Vec::new(),
+        });
+        self.pages
+            .borrow_mut()
+            .insert(source_path.to_path_buf(), history.clone());
+        history
+    }
+}
+
+fn git_history(path: &Path) -> Option<PageHistory> {
+    let dir = path.parent()?;
+    let output = Command::new("git")
+        .arg("log")
+        .arg("--follow")
+        .arg("--format=%cs%x09%an")
+        .arg("--")
+        .arg(path.file_name()?)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_log(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse "date\tauthor" lines (newest first); None when the file has no commits
+fn parse_log(log: &str) -> Option<PageHistory> {
+    let mut history = PageHistory::default();
+    for line in log.lines() {
+        let Some((date, author)) = line.split_once('\t') else {
+            continue;
+        };
+        if history.last_updated.is_none() {
+            history.last_updated = Some(date.to_string());
+        }
+        if !history.contributors.iter().any(|a| a == author) {
+            history.contributors.push(author.to_string());
+        }
+    }
+    history.last_updated.is_some().then_some(history)
+}
+
+fn mtime_date(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let secs = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(crate::sitemap::format_date(secs))
+}
+
+/// Footer shown below the page content (empty when nothing is known)
+pub fn footer_html(history: &PageHistory) -> String {
+    if history.last_updated.is_none() && history.contributors.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from("<footer class=\"page-footer\">\n");
+    if let Some(date) = &history.last_updated {
+        html.push_str(&format!(
+            "  <span class=\"page-last-updated\">Last updated: <time datetime=\"{0}\">{0}</time></span>\n",
+            attr_escape(date)
+        ));
+    }
+    if !history.contributors.is_empty() {
+        let names: Vec<String> = history
+            .contributors
+            .iter()
+            .map(|a| attr_escape(a))
+            .collect();
+        html.push_str(&format!(
+            "  <span class=\"page-contributors\">Contributors: {}</span>\n",
+            names.join(", ")
+        ));
+    }
+    html.push_str("</footer>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let log = "2026-10-18\tAlice\n2026-09-01\tBob <x>\n2026-08-01\tAlice\n";
+        let history = parse_log(log).unwrap();
+        assert_eq!(history.last_updated.as_deref(), Some("2026-10-18"));
+        assert_eq!(history.contributors, vec!["Alice", "Bob <x>"]);
+        assert_eq!(parse_log(""), None);
+        assert_eq!(footer_html(&PageHistory::default()), "");
+
+        let footer = footer_html(&history);
+        assert!(footer.contains("<time datetime=\"2026-10-18\">2026-10-18</time>"));
+        assert!(footer.contains("Contributors: Alice, Bob &lt;x&gt;"));
+    }
+
+    #[test]
+    fn test_mtime_fallback() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-history");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("page.md");
+        fs::write(&path, "# Page").unwrap();
+
+        let history = History::default();
+        let page = history.page(&path);
+        // Outside a repository (or untracked): mtime date, no contributors
+        assert_eq!(page.last_updated.as_ref().map(|d| d.len()), Some(10));
+        assert!(page.contributors.is_empty());
+        assert_eq!(history.page(&path), page);
+        assert_eq!(History::disabled().page(&path), PageHistory::default());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+}
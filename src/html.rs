@@ -0,0 +1,152 @@
+/// Whether a URL points outside the book (scheme or data URI)
+pub fn is_external(url: &str) -> bool {
+    url.contains("://") || url.starts_with("mailto:") || url.starts_with("data:")
+}
+
+/// Join a relative path onto a directory ("a/" + "../b.html" -> "b.html")
+pub fn resolve_relative(dir: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in path.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Rewrite every `attr="value"` in an HTML string
+pub fn rewrite_attribute(html: &str, attr: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let pattern = format!("{}=\"", attr);
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(pos) = rest.find(&pattern) {
+        // Only match whole attribute names (preceded by whitespace)
+        let preceded_by_space = rest[..pos]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_whitespace());
+        let value_start = pos + pattern.len();
+        let Some(value_len) = rest[value_start..].find('"') else {
+            break;
+        };
+        result.push_str(&rest[..value_start]);
+        let value = &rest[value_start..value_start + value_len];
+        if preceded_by_space {
+            result.push_str(&f(value));
+        } else {
+            result.push_str(value);
+        }
+        rest = &rest[value_start + value_len..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Decode %XX escapes (invalid sequences are kept as-is)
+pub fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = input
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Media type of an image file, by extension
+pub fn media_type(path: &str) -> Option<&'static str> {
+    let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "svg" => Some("image/svg+xml"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Encode bytes as standard base64 (with padding)
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        result.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        result.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        if chunk.len() > 1 {
+            result.push(ALPHABET[(n >> 6) as usize & 63] as char);
+        } else {
+            result.push('=');
+        }
+        if chunk.len() > 2 {
+            result.push(ALPHABET[n as usize & 63] as char);
+        } else {
+            result.push('=');
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_relative() {
+        assert_eq!(resolve_relative("", "a.html"), "a.html");
+        assert_eq!(resolve_relative("a/", "../b.html"), "b.html");
+        assert_eq!(resolve_relative("a/b/", "./c/d.png"), "a/b/c/d.png");
+    }
+
+    #[test]
+    fn test_rewrite_attribute() {
+        let html = r#"<a href="x" data-href="y"><img src="z"></a>"#;
+        let out = rewrite_attribute(html, "href", |v| v.to_uppercase());
+        assert_eq!(out, r#"<a href="X" data-href="y"><img src="z"></a>"#);
+    }
+
+    #[test]
+    fn test_media_type() {
+        assert_eq!(media_type("a/b.PNG"), Some("image/png"));
+        assert_eq!(media_type("c.svg"), Some("image/svg+xml"));
+        assert_eq!(media_type("d.txt"), None);
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("2-Getting%20Started"), "2-Getting Started");
+        assert_eq!(percent_decode("%E6%97%A5"), "日");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+}
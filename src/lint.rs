@@ -0,0 +1,333 @@
+use crate::book::{Book, PageInfo};
+use crate::config::{LintConfig, Severity};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// A rule violation reported by `unibook lint`
+#[derive(Debug)]
+pub struct LintMessage {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub source_path: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+/// The severity is shown by the log level ("Error: " / "Warning: ")
+impl fmt::Display for LintMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: [{}] {}",
+            self.source_path.display(),
+            self.line,
+            self.rule,
+            self.message
+        )
+    }
+}
+
+/// Checks page sources against the authoring rules in `[lint]`
+pub struct Linter<'a> {
+    book: &'a Book,
+    config: &'a LintConfig,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(book: &'a Book) -> Self {
+        Self {
+            book,
+            config: &book.config.lint,
+        }
+    }
+
+    pub fn run(&self) -> Result<Vec<LintMessage>> {
+        let mut messages = Vec::new();
+        for page in crate::builder::Builder::collect_pages(&self.book.items) {
+            let content = fs::read_to_string(&page.source_path)
+                .context(format!("Failed to read {}", page.source_path.display()))?;
+            messages.extend(self.lint_page(page, &content));
+        }
+        Ok(messages)
+    }
+
+    fn lint_page(&self, page: &PageInfo, content: &str) -> Vec<LintMessage> {
+        let mut messages = Vec::new();
+        let mut report = |rule: &'static str, severity: Severity, line: usize, message: String| {
+            if severity != Severity::Off {
+                messages.push(LintMessage {
+                    rule,
+                    severity,
+                    source_path: page.source_path.clone(),
+                    line,
+                    message,
+                });
+            }
+        };
+
+        // Front matter is not Markdown; line numbers still count its lines
+        let (front_matter, body) = crate::meta::FrontMatter::parse(content);
+        let front_matter_lines = content[..content.len() - body.len()].lines().count();
+
+        let mut fence: Option<(char, usize)> = None;
+        let mut first_h1: Option<(usize, String)> = None;
+        let mut previous_level: Option<usize> = None;
+        let mut h2_lines: HashMap<String, usize> = HashMap::new();
+
+        for (index, line) in body.lines().enumerate() {
+            let line_number = front_matter_lines + index + 1;
+
+            if line.ends_with([' ', '\t']) {
+                report(
+                    "trailing_whitespace",
+                    self.config.trailing_whitespace,
+                    line_number,
+                    "trailing whitespace".to_string(),
+                );
+            }
+
+            // Fenced code blocks: only the opening fence is checked
+            let trimmed = line.trim_start();
+            if let Some((fence_char, fence_len)) = fence {
+                let closing = trimmed.chars().take_while(|&c| c == fence_char).count();
+                if closing >= fence_len && trimmed[closing..].trim().is_empty() {
+                    fence = None;
+                }
+                continue;
+            }
+            if let Some((fence_char, fence_len, info)) = fence_start(trimmed) {
+                if info.is_empty() {
+                    report(
+                        "code_block_language",
+                        self.config.code_block_language,
+                        line_number,
+                        "fenced code block without a language".to_string(),
+                    );
+                }
+                fence = Some((fence_char, fence_len));
+                continue;
+            }
+
+            if unbalanced_math(line) {
+                report(
+                    "unbalanced_math",
+                    self.config.unbalanced_math,
+                    line_number,
+                    "unbalanced `$` math delimiter".to_string(),
+                );
+            }
+
+            let Some((level, title)) = heading(line) else {
+                continue;
+            };
+
+            if let Some(previous) = previous_level
+                && level > previous + 1
+            {
+                report(
+                    "heading_increment",
+                    self.config.heading_increment,
+                    line_number,
+                    format!("heading level skipped (h{} after h{})", level, previous),
+                );
+            }
+            previous_level = Some(level);
+
+            if level == 1 && first_h1.is_none() {
+                first_h1 = Some((line_number, title.to_string()));
+            }
+
+            if level == 2 {
+                if let Some(first_line) = h2_lines.get(title) {
+                    report(
+                        "duplicate_h2",
+                        self.config.duplicate_h2,
+                        line_number,
+                        format!(
+                            "duplicate H2 \"{}\" (id {} is also used on line {})",
+                            title,
+                            crate::book::section_id(title),
+                            first_line
+                        ),
+                    );
+                } else {
+                    h2_lines.insert(title.to_string(), line_number);
+                }
+            }
+        }
+
+        match first_h1 {
+            Some((line, h1)) if h1 != page.title.trim() => report(
+                "first_h1_title",
+                self.config.first_h1_title,
+                line,
+                format!(
+                    "first H1 \"{}\" does not match the title in book.toml \"{}\"",
+                    h1, page.title
+                ),
+            ),
+            Some(_) => {}
+            // The title comes from the front matter, so no H1 is needed
+            None if front_matter.get("title").is_some() => {}
+            None => report(
+                "first_h1_title",
+                self.config.first_h1_title,
+                1,
+                format!("page has no H1 (expected \"{}\")", page.title),
+            ),
+        }
+
+        messages
+    }
+}
+
+/// "```rust" -> ('`', 3, "rust")
+fn fence_start(line: &str) -> Option<(char, usize, &str)> {
+    let fence_char = line.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = line.chars().take_while(|&c| c == fence_char).count();
+    (len >= 3).then(|| (fence_char, len, line[len..].trim()))
+}
+
+/// ATX heading: "## Title ##" -> (2, "Title")
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// An odd number of unescaped `$` outside inline code spans, unless a `$`
+/// followed by a digit can be read as a price ("costs $5")
+fn unbalanced_math(line: &str) -> bool {
+    let mut count = 0;
+    let mut price = false;
+    let mut in_code = false;
+    let mut escaped = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '`' => in_code = !in_code,
+            '$' if !in_code => {
+                count += 1;
+                price |= chars.peek().is_some_and(|c| c.is_ascii_digit());
+            }
+            _ => {}
+        }
+    }
+    count % 2 == 1 && !price
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint(title: &str, content: &str, lint_toml: &str) -> Vec<LintMessage> {
+        let config: Config = toml::from_str(&format!(
+            "[book]\ntitle = \"Test\"\n[lint]\n{}\n[[pages]]\ntitle = \"Intro\"\npath = \"intro.md\"\n",
+            lint_toml
+        ))
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![],
+        };
+        let page = PageInfo {
+            title: title.to_string(),
+            source_path: PathBuf::from("src/intro.md"),
+            output_filename: "intro.html".to_string(),
+            url: "/intro.html".to_string(),
+            sections: vec![],
+            word_count: 0,
+        };
+        Linter::new(&book).lint_page(&page, content)
+    }
+
+    fn rules(messages: &[LintMessage]) -> Vec<(&str, usize)> {
+        messages.iter().map(|m| (m.rule, m.line)).collect()
+    }
+
+    #[test]
+    fn test_clean_page() {
+        let content = "# Intro\n\n## A\n\n### A.1\n\n```rust\nfn main() {}\n```\n\n$x$ and `$`\n";
+        assert!(rules(&lint("Intro", content, "")).is_empty());
+        let content = "# Intro\n\nIt costs $5, or $x$ per item.\n";
+        assert!(rules(&lint("Intro", content, "")).is_empty());
+    }
+
+    #[test]
+    fn test_rules() {
+        let content = concat!(
+            "# Welcome\n",       // 1: title mismatch
+            "\n",                // 2
+            "### Skipped\n",     // 3: h3 after h1
+            "## Setup\n",        // 4
+            "Costs $x \n",       // 5: unbalanced $, trailing space
+            "## Setup\n",        // 6: duplicate
+            "```\n",             // 7: no language
+            "# not a heading\n", // 8: inside code
+            "```\n",             // 9
+        );
+        let messages = lint("Intro", content, "");
+        let mut found = rules(&messages);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("code_block_language", 7),
+                ("duplicate_h2", 6),
+                ("first_h1_title", 1),
+                ("heading_increment", 3),
+                ("trailing_whitespace", 5),
+                ("unbalanced_math", 5),
+            ]
+        );
+        let duplicate = messages.iter().find(|m| m.rule == "duplicate_h2").unwrap();
+        assert_eq!(duplicate.severity, Severity::Error);
+        assert!(duplicate.message.contains("2-Setup"));
+        assert!(duplicate.message.contains("line 4"));
+    }
+
+    #[test]
+    fn test_severity_config() {
+        let content = "## No H1 \n";
+        let messages = lint(
+            "Intro",
+            content,
+            "trailing_whitespace = \"off\"\nfirst_h1_title = \"error\"",
+        );
+        assert_eq!(rules(&messages), vec![("first_h1_title", 1)]);
+        assert_eq!(messages[0].severity, Severity::Error);
+        assert_eq!(
+            messages[0].to_string(),
+            "src/intro.md:1: [first_h1_title] page has no H1 (expected \"Intro\")"
+        );
+    }
+
+    #[test]
+    fn test_front_matter() {
+        let content = concat!(
+            "---\n",              // 1
+            "title: Intro\n",     // 2
+            "description: $5 \n", // 3: not Markdown
+            "---\n",              // 4
+            "\n",                 // 5
+            "Text \n",            // 6: trailing space
+        );
+        // The title comes from the front matter, so the missing H1 is fine
+        assert_eq!(
+            rules(&lint("Intro", content, "")),
+            vec![("trailing_whitespace", 6)]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::book::{Book, BookItem, PageInfo};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Include directive expanded in Markdown outputs: `@[include](path/to/file.md)`
+const INCLUDE_PREFIX: &str = "@[include](";
+
+/// Nested includes deeper than this are left as-is (guards against cycles)
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+pub struct LlmsGenerator;
+
+impl LlmsGenerator {
+    /// Write llms.txt, llms-full.txt and a .md mirror next to each page
+    pub fn generate(book: &Book, output_dir: &Path) -> Result<()> {
+        let pages = crate::builder::Builder::collect_pages(&book.items);
+
+        let mut full = Self::header(book);
+        for page in &pages {
+            let markdown = Self::page_markdown(page)?;
+
+            let mirror_path = output_dir.join(Self::mirror_filename(page));
+            if let Some(parent) = mirror_path.parent() {
+                fs::create_dir_all(parent).context("Failed to create output subdirectories")?;
+            }
+            fs::write(&mirror_path, &markdown)
+                .context(format!("Failed to write {}", mirror_path.display()))?;
+
+            full.push_str("---\n\n");
+            full.push_str(&format!("Source: {}\n\n", Self::url(book, page)));
+            full.push_str(markdown.trim());
+            full.push_str("\n\n");
+        }
+
+        fs::write(output_dir.join("llms.txt"), Self::index(book))
+            .context("Failed to write llms.txt")?;
+        fs::write(output_dir.join("llms-full.txt"), full)
+            .context("Failed to write llms-full.txt")?;
+        Ok(())
+    }
+
+    /// Page source with includes expanded
+    pub fn page_markdown(page: &PageInfo) -> Result<String> {
+        Ok(Self::page_markdown_with_origins(page)?.0)
+    }
+
+    /// Page source with includes expanded, and for each of its lines the
+    /// file and 1-based line it comes from
+    pub fn page_markdown_with_origins(page: &PageInfo) -> Result<(String, Vec<LineOrigin>)> {
+        let content = fs::read_to_string(&page.source_path)
+            .context(format!("Failed to read {}", page.source_path.display()))?;
+        let mut origins = Vec::new();
+        let markdown = expand_includes(&content, &page.source_path, 0, &mut origins);
+        Ok((markdown, origins))
+    }
+
+    /// a/b.html -> a/b.md
+    pub fn mirror_filename(page: &PageInfo) -> String {
+        format!("{}.md", page.slug())
+    }
+
+    fn url(book: &Book, page: &PageInfo) -> String {
+        let mirror = Self::mirror_filename(page);
+        book.absolute_url(&mirror).unwrap_or_else(|| {
+            format!(
+                "{}/{}",
+                crate::toc::normalize_base_path(&book.config.build.base_path),
+                mirror
+            )
+        })
+    }
+
+    fn header(book: &Book) -> String {
+        let mut text = format!("# {}\n\n", book.config.book.title);
+        if let Some(description) = &book.config.book.description {
+            text.push_str(&format!("> {}\n\n", description));
+        }
+        text
+    }
+
+    /// llms.txt: title, description and links grouped by Part
+    fn index(book: &Book) -> String {
+        let mut text = Self::header(book);
+
+        // Pages outside any Part come first
+        let loose: Vec<&PageInfo> = book
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                BookItem::Page(page) => Some(page),
+                BookItem::Part { .. } => None,
+            })
+            .collect();
+        if !loose.is_empty() {
+            text.push_str("## Pages\n\n");
+            for page in loose {
+                text.push_str(&Self::link(book, page));
+            }
+            text.push('\n');
+        }
+
+        for item in &book.items {
+            if let BookItem::Part { title, children } = item {
+                Self::part(book, &mut text, title, children);
+            }
+        }
+        text
+    }
+
+    fn part(book: &Book, text: &mut String, title: &str, children: &[BookItem]) {
+        let pages: Vec<&PageInfo> = children
+            .iter()
+            .filter_map(|item| match item {
+                BookItem::Page(page) => Some(page),
+                BookItem::Part { .. } => None,
+            })
+            .collect();
+        if !pages.is_empty() {
+            text.push_str(&format!("## {}\n\n", title));
+            for page in pages {
+                text.push_str(&Self::link(book, page));
+            }
+            text.push('\n');
+        }
+
+        // Nested parts get their own group, named after the path of parts
+        for item in children {
+            if let BookItem::Part {
+                title: child_title,
+                children,
+            } = item
+            {
+                Self::part(
+                    book,
+                    text,
+                    &format!("{} > {}", title, child_title),
+                    children,
+                );
+            }
+        }
+    }
+
+    fn link(book: &Book, page: &PageInfo) -> String {
+        format!("- [{}]({})\n", page.title, Self::url(book, page))
+    }
+}
+
+/// Source file and 1-based line of a line of expanded Markdown
+pub type LineOrigin = (PathBuf, usize);
+
+/// Replace `@[include](path)` lines with the referenced file, recursively,
+/// recording where each resulting line comes from in `origins`
+fn expand_includes(
+    content: &str,
+    source_path: &Path,
+    depth: usize,
+    origins: &mut Vec<LineOrigin>,
+) -> String {
+    let mut result = String::with_capacity(content.len());
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let target = line
+            .trim()
+            .strip_prefix(INCLUDE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(')'));
+        let included = target
+            .filter(|_| depth < MAX_INCLUDE_DEPTH)
+            .and_then(|target| {
+                let path = source_path.parent()?.join(target);
+                match fs::read_to_string(&path) {
+                    Ok(included) => Some(expand_includes(&included, &path, depth + 1, origins)),
+                    Err(_) => {
+                        crate::log::warning!(
+                            "included file not found: {} (in {})",
+                            target,
+                            source_path.display()
+                        );
+                        None
+                    }
+                }
+            });
+
+        match included {
+            Some(included) => {
+                result.push_str(&included);
+                if included.is_empty() {
+                    // The blank line left in place of an empty file
+                    origins.push((source_path.to_path_buf(), index + 1));
+                }
+                if !included.ends_with('\n') {
+                    result.push('\n');
+                }
+            }
+            None => {
+                result.push_str(line);
+                origins.push((source_path.to_path_buf(), index + 1));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn page(title: &str, output_filename: &str) -> PageInfo {
+        PageInfo {
+            title: title.to_string(),
+            source_path: PathBuf::from(output_filename.replace(".html", ".md")),
+            output_filename: output_filename.to_string(),
+            url: format!("/{}", output_filename),
+            sections: vec![],
+            word_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_expand_includes() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-llms-include");
+        std::fs::create_dir_all(temp_dir.join("parts")).unwrap();
+        std::fs::write(temp_dir.join("parts/a.md"), "A\n@[include](b.md)\n").unwrap();
+        std::fs::write(temp_dir.join("parts/b.md"), "B").unwrap();
+
+        let content = "# Title\n@[include](parts/a.md)\n@[include](missing.md)\nEnd\n";
+        let page = temp_dir.join("page.md");
+        let mut origins = Vec::new();
+        let expanded = expand_includes(content, &page, 0, &mut origins);
+        assert_eq!(expanded, "# Title\nA\nB\n@[include](missing.md)\nEnd\n");
+
+        // Lines map back to the file they come from
+        let a = temp_dir.join("parts/a.md");
+        let b = temp_dir.join("parts/b.md");
+        assert_eq!(
+            origins,
+            vec![
+                (page.clone(), 1),
+                (a, 1),
+                (b, 1),
+                (page.clone(), 3),
+                (page, 4)
+            ]
+        );
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_index_groups_by_part() {
+        let config: Config = toml::from_str(
+            r#"
+[book]
+title = "Test Book"
+description = "About tests"
+
+[build]
+base_path = "book"
+
+[[pages]]
+title = "Intro"
+path = "intro.md"
+"#,
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![
+                BookItem::Page(page("Intro", "intro.html")),
+                BookItem::Part {
+                    title: "Part 1".to_string(),
+                    children: vec![
+                        BookItem::Page(page("Setup", "guide/setup.html")),
+                        BookItem::Part {
+                            title: "Advanced".to_string(),
+                            children: vec![BookItem::Page(page("Tips", "tips.html"))],
+                        },
+                    ],
+                },
+            ],
+        };
+
+        let text = LlmsGenerator::index(&book);
+        assert!(text.starts_with("# Test Book\n\n> About tests\n\n"));
+        assert!(text.contains("## Pages\n\n- [Intro](/book/intro.md)\n"));
+        assert!(text.contains("## Part 1\n\n- [Setup](/book/guide/setup.md)\n"));
+        assert!(text.contains("## Part 1 > Advanced\n\n- [Tips](/book/tips.md)\n"));
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Environment variable overriding the default level (`error`, `warn`, `info`, `debug`, `trace`)
+pub const LOG_ENV: &str = "UNIBOOK_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Level> {
+        [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ]
+        .into_iter()
+        .find(|level| level.name().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// `-q` / `-v` / `-vv` take precedence over UNIBOOK_LOG; the default is info
+    pub fn from_args(quiet: bool, verbose: u8, env: Option<&str>) -> Result<Level> {
+        if quiet {
+            return Ok(Level::Warn);
+        }
+        match verbose {
+            0 => {}
+            1 => return Ok(Level::Debug),
+            _ => return Ok(Level::Trace),
+        }
+        match env.filter(|value| !value.trim().is_empty()) {
+            Some(value) => Level::parse(value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid {} value `{}` (expected error, warn, info, debug or trace)",
+                    LOG_ENV,
+                    value
+                )
+            }),
+            None => Ok(Level::Info),
+        }
+    }
+}
+
+/// Output format of `--log-format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines (default)
+    Text,
+    /// One JSON object per line on stderr, including build events
+    Json,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+static JSON: AtomicBool = AtomicBool::new(false);
+
+pub fn init(level: Level, format: LogFormat) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    JSON.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Called through the `error!` / `warning!` / `info!` / `debug!` / `trace!` macros
+pub fn log(level: Level, args: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+    // stderr, so JSON logs never mix with `--json` output on stdout
+    if json() {
+        eprintln!(
+            "{}",
+            serde_json::json!({ "level": level.name(), "message": args.to_string().trim() })
+        );
+        return;
+    }
+    match level {
+        Level::Error => eprintln!("Error: {}", args),
+        Level::Warn => eprintln!("Warning: {}", args),
+        _ => println!("{}", args),
+    }
+}
+
+/// Build events for other tools (`--log-format json`)
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    PageStarted {
+        source_path: &'a Path,
+        output_filename: &'a str,
+    },
+    PageFinished {
+        source_path: &'a Path,
+        output_filename: &'a str,
+        duration_ms: u64,
+    },
+    PageFailed {
+        source_path: &'a Path,
+        output_filename: &'a str,
+        error: String,
+    },
+    RebuildTriggered {
+        paths: &'a [PathBuf],
+    },
+}
+
+impl Event<'_> {
+    fn level(&self) -> Level {
+        match self {
+            Event::PageStarted { .. } | Event::RebuildTriggered { .. } => Level::Info,
+            Event::PageFinished { .. } => Level::Debug,
+            Event::PageFailed { .. } => Level::Error,
+        }
+    }
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::PageStarted {
+                source_path,
+                output_filename,
+            } => write!(
+                f,
+                "Building: {} -> {}",
+                source_path.display(),
+                output_filename
+            ),
+            Event::PageFinished {
+                output_filename,
+                duration_ms,
+                ..
+            } => write!(f, "Built {} in {} ms", output_filename, duration_ms),
+            Event::PageFailed {
+                source_path, error, ..
+            } => {
+                // The full error (e.g. unidoc's stderr) is in the failure summary
+                let first_line = error
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches(':');
+                write!(
+                    f,
+                    "Failed to build {}: {}",
+                    source_path.display(),
+                    first_line
+                )
+            }
+            Event::RebuildTriggered { paths } => {
+                let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "Change detected: {}", paths.join(", "))
+            }
+        }
+    }
+}
+
+pub fn event(event: Event) {
+    let level = event.level();
+    if !enabled(level) {
+        return;
+    }
+    if json() {
+        let mut value = serde_json::to_value(&event).unwrap_or_default();
+        value["level"] = level.name().into();
+        eprintln!("{}", value);
+    } else {
+        log(level, format_args!("{}", event));
+    }
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Error, format_args!($($arg)*)) };
+}
+macro_rules! warning {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Trace, format_args!($($arg)*)) };
+}
+// `warning!` rather than `warn!`, which would clash with the built-in #[warn] attribute
+pub(crate) use {debug, error, info, trace, warning};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_args() {
+        assert_eq!(Level::from_args(false, 0, None).unwrap(), Level::Info);
+        assert_eq!(
+            Level::from_args(true, 0, Some("trace")).unwrap(),
+            Level::Warn
+        );
+        assert_eq!(Level::from_args(false, 1, None).unwrap(), Level::Debug);
+        assert_eq!(
+            Level::from_args(false, 3, Some("error")).unwrap(),
+            Level::Trace
+        );
+        assert_eq!(
+            Level::from_args(false, 0, Some("DEBUG")).unwrap(),
+            Level::Debug
+        );
+        assert_eq!(Level::from_args(false, 0, Some("")).unwrap(), Level::Info);
+        assert!(Level::from_args(false, 0, Some("loud")).is_err());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let source_path = PathBuf::from("src/intro.md");
+        let event = Event::PageFinished {
+            source_path: &source_path,
+            output_filename: "intro.html",
+            duration_ms: 12,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"page_finished","source_path":"src/intro.md","output_filename":"intro.html","duration_ms":12}"#
+        );
+        assert_eq!(event.to_string(), "Built intro.html in 12 ms");
+    }
+}
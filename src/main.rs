@@ -1,14 +1,32 @@
 mod book;
 mod builder;
+mod check;
 mod config;
+mod edit_link;
+mod epub;
+mod external;
+mod history;
+mod html;
+mod lint;
+mod llms;
+mod log;
+mod meta;
+mod not_found;
+mod orphans;
+mod print;
+mod report;
 mod search;
 mod search_assets;
+mod search_tokenizer;
+mod single_html;
+mod sitemap;
 mod toc;
 mod unidoc;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use std::env;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
@@ -17,6 +35,15 @@ use std::path::{Path, PathBuf};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Only print warnings and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print more detail (-v: debug, -vv: trace); overrides UNIBOOK_LOG
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Log format (json emits one object per line, including build events)
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: log::LogFormat,
 }
 
 #[derive(Subcommand)]
@@ -26,6 +53,69 @@ enum Commands {
         /// Path to the directory containing book.toml (default: current directory)
         #[arg(short, long, default_value = ".")]
         dir: PathBuf,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "html")]
+        format: builder::OutputFormat,
+        /// Check internal links, anchors and images after building
+        #[arg(long, default_value = "false")]
+        check: bool,
+        /// Treat warnings (e.g. orphan files) as errors
+        #[arg(long, default_value = "false")]
+        deny_warnings: bool,
+        /// Write per-page timings and sizes to a JSON file
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Keep building the other pages when one fails, then report all failures
+        #[arg(long, default_value = "false")]
+        keep_going: bool,
+        /// Treat all warnings as errors and also run the link check and lint
+        #[arg(long, default_value = "false")]
+        strict: bool,
+    },
+    /// Build the book and check internal links, anchors and images
+    Check {
+        /// Path to the directory containing book.toml (default: current directory)
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+        /// Also request external URLs (see [check] in book.toml)
+        #[arg(long, default_value = "false")]
+        external: bool,
+        /// Treat warnings (e.g. orphan files) as errors
+        #[arg(long, default_value = "false")]
+        deny_warnings: bool,
+        /// Treat all warnings as errors and also run lint
+        #[arg(long, default_value = "false")]
+        strict: bool,
+    },
+    /// Check page sources against the authoring rules in [lint]
+    Lint {
+        /// Path to the directory containing book.toml (default: current directory)
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Search the book sources with the same ranking as the web search
+    Search {
+        /// Words to search for
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Path to the directory containing book.toml (default: current directory)
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+        /// Maximum number of matches to print
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+        /// Print as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+    /// Show the resolved book structure (parts, pages, sections, word counts)
+    Metadata {
+        /// Path to the directory containing book.toml (default: current directory)
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+        /// Print as JSON, including the config
+        #[arg(long, default_value = "false")]
+        json: bool,
     },
     /// Initialize a new book
     Init {
@@ -55,30 +145,232 @@ enum Commands {
 
 fn main() {
     if let Err(e) = run() {
-        eprintln!("Error: {}", e);
+        let mut message = e.to_string();
         for cause in e.chain().skip(1) {
-            eprintln!("  Caused by: {}", cause);
+            message.push_str(&format!("\n  Caused by: {}", cause));
         }
+        log::error!("{}", message);
         std::process::exit(1);
     }
 }
 
 fn run() -> Result<()> {
     let cli = Cli::parse();
+    let level = log::Level::from_args(
+        cli.quiet,
+        cli.verbose,
+        env::var(log::LOG_ENV).ok().as_deref(),
+    )?;
+    log::init(level, cli.log_format);
 
     match cli.command {
-        Commands::Build { dir } => build_book(&dir),
+        Commands::Build {
+            dir,
+            format,
+            check,
+            deny_warnings,
+            report,
+            keep_going,
+            strict,
+        } => build_book(
+            &dir,
+            format,
+            BuildOptions {
+                check_links: check.then_some(builder::LinkCheck::Internal),
+                deny_warnings,
+                report,
+                keep_going,
+                strict,
+            },
+        ),
+        Commands::Check {
+            dir,
+            external,
+            deny_warnings,
+            strict,
+        } => {
+            let check = if external {
+                builder::LinkCheck::External
+            } else {
+                builder::LinkCheck::Internal
+            };
+            build_book(
+                &dir,
+                builder::OutputFormat::Html,
+                BuildOptions {
+                    check_links: Some(check),
+                    deny_warnings,
+                    strict,
+                    ..Default::default()
+                },
+            )
+        }
+        Commands::Lint { dir } => lint_book(&dir),
+        Commands::Search {
+            query,
+            dir,
+            limit,
+            json,
+        } => search_book(&dir, &query.join(" "), limit, json),
+        Commands::Metadata { dir, json } => show_metadata(&dir, json),
         Commands::Init { dir } => init_book(&dir),
         Commands::Serve { dir, port } => serve_book(&dir, port),
         Commands::Watch { dir, dev } => watch_book(&dir, dev),
     }
 }
 
-fn build_book(dir: &Path) -> Result<()> {
+/// Flags shared by `build` and `check`
+#[derive(Default)]
+struct BuildOptions {
+    check_links: Option<builder::LinkCheck>,
+    deny_warnings: bool,
+    report: Option<PathBuf>,
+    keep_going: bool,
+    /// Also enabled by `[build] strict = true`
+    strict: bool,
+}
+
+impl BuildOptions {
+    /// serve / watch: render every page that builds
+    fn preview() -> Self {
+        Self {
+            keep_going: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Page failures were already reported by the build; keep serving the rest
+fn keep_serving(result: Result<()>) -> Result<()> {
+    match result {
+        Err(e) if e.is::<report::BuildFailures>() => Ok(()),
+        result => result,
+    }
+}
+
+fn build_book(dir: &Path, format: builder::OutputFormat, options: BuildOptions) -> Result<()> {
     // Check if unidoc is available
     unidoc::check_unidoc_available()?;
 
-    // Load config
+    let book = load_book(dir)?;
+
+    // Strict: warnings are errors, and the lint and link check run as part of the build
+    let strict = options.strict || book.config.build.strict;
+    let deny_warnings = options.deny_warnings || strict;
+    let mut check_links = options.check_links;
+    if strict {
+        let warnings = book.config.warnings().len();
+        if warnings > 0 {
+            anyhow::bail!("Found {} warning(s) in book.toml (strict mode)", warnings);
+        }
+        report_lint(&lint::Linter::new(&book).run()?, true)?;
+        if format == builder::OutputFormat::Html {
+            check_links = check_links.or(Some(builder::LinkCheck::Internal));
+        }
+    }
+
+    // Build
+    let builder = builder::Builder::new(book, dir)
+        .context("Failed to create builder")?
+        .deny_warnings(deny_warnings)
+        .report(options.report)
+        .keep_going(options.keep_going);
+    builder.build_format(format)?;
+
+    if let Some(check) = check_links {
+        builder.check_links(check)?;
+    }
+
+    Ok(())
+}
+
+fn lint_book(dir: &Path) -> Result<()> {
+    let book = load_book(dir)?;
+    report_lint(&lint::Linter::new(&book).run()?, false)
+}
+
+/// Log lint messages; fails on errors (or on any message when deny_warnings is set)
+fn report_lint(messages: &[lint::LintMessage], deny_warnings: bool) -> Result<()> {
+    let mut errors = 0;
+    for message in messages {
+        if deny_warnings || message.severity == config::Severity::Error {
+            errors += 1;
+            log::error!("{}", message);
+        } else {
+            log::warning!("{}", message);
+        }
+    }
+    let warnings = messages.len() - errors;
+    log::info!("{} error(s), {} warning(s)", errors, warnings);
+
+    if errors > 0 {
+        anyhow::bail!("Lint failed with {} error(s)", errors);
+    }
+    Ok(())
+}
+
+fn search_book(dir: &Path, query: &str, limit: usize, json: bool) -> Result<()> {
+    let book = load_book(dir)?;
+    let index = search::SearchIndexGenerator::build_index(&book, &history::History::disabled())?;
+    let matches = index.search(query, limit);
+
+    if json {
+        let output =
+            serde_json::to_string_pretty(&matches).context("Failed to serialize search results")?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    if matches.is_empty() {
+        log::info!("No results found for {:?}", query);
+    }
+    let color = std::io::stdout().is_terminal();
+    for found in &matches {
+        let title = match &found.section {
+            Some(section) => format!("{} > {}", found.title, section),
+            None => found.title.clone(),
+        };
+        println!("{}:{}: {}", found.source_path.display(), found.line, title);
+        println!(
+            "    {}",
+            highlight(&found.snippet, &found.highlights, color)
+        );
+    }
+    Ok(())
+}
+
+/// Bold the highlighted ranges on a terminal
+fn highlight(snippet: &str, ranges: &[(usize, usize)], color: bool) -> String {
+    if !color {
+        return snippet.to_string();
+    }
+    let mut text = String::new();
+    let mut last = 0;
+    for &(start, end) in ranges {
+        text.push_str(&snippet[last..start]);
+        text.push_str(&format!("\x1b[1;33m{}\x1b[0m", &snippet[start..end]));
+        last = end;
+    }
+    text.push_str(&snippet[last..]);
+    text
+}
+
+fn show_metadata(dir: &Path, json: bool) -> Result<()> {
+    let book = load_book(dir)?;
+
+    if json {
+        let output =
+            serde_json::to_string_pretty(&book).context("Failed to serialize book metadata")?;
+        println!("{}", output);
+    } else {
+        print!("{}", book.outline());
+    }
+
+    Ok(())
+}
+
+/// Load book.toml from `dir` and resolve the book structure
+fn load_book(dir: &Path) -> Result<book::Book> {
     let config_path = dir.join("book.toml");
     if !config_path.exists() {
         anyhow::bail!(
@@ -88,15 +380,11 @@ fn build_book(dir: &Path) -> Result<()> {
     }
 
     let config = config::Config::from_file(&config_path).context("Failed to load book.toml")?;
+    for warning in config.warnings() {
+        log::warning!("book.toml: {}", warning);
+    }
 
-    // Create book
-    let book = book::Book::from_config(config, dir).context("Failed to create book")?;
-
-    // Build
-    let builder = builder::Builder::new(book, dir).context("Failed to create builder")?;
-    builder.build()?;
-
-    Ok(())
+    book::Book::from_config(config, dir).context("Failed to create book")
 }
 
 fn init_book(dir: &PathBuf) -> Result<()> {
@@ -143,12 +431,12 @@ path = "chapter1.md"
 "#;
 
     std::fs::write(&book_toml_path, book_toml_content).context("Failed to create book.toml")?;
-    println!("Created {}", book_toml_path.display());
+    log::info!("Created {}", book_toml_path.display());
 
     // Create src directory
     let src_dir = dir.join("src");
     std::fs::create_dir_all(&src_dir).context("Failed to create src directory")?;
-    println!("Created {}/", src_dir.display());
+    log::info!("Created {}/", src_dir.display());
 
     // Create intro.md
     let intro_path = src_dir.join("intro.md");
@@ -170,7 +458,7 @@ The table of contents will appear on the left side of each page, allowing reader
 "#;
 
     std::fs::write(&intro_path, intro_content).context("Failed to create intro.md")?;
-    println!("Created {}", intro_path.display());
+    log::info!("Created {}", intro_path.display());
 
     // Create chapter1.md
     let chapter1_path = src_dir.join("chapter1.md");
@@ -188,10 +476,10 @@ Add more content here.
 "#;
 
     std::fs::write(&chapter1_path, chapter1_content).context("Failed to create chapter1.md")?;
-    println!("Created {}", chapter1_path.display());
+    log::info!("Created {}", chapter1_path.display());
 
-    println!("\nBook initialized successfully!");
-    println!("Run 'unibook build' to build your book.");
+    log::info!("\nBook initialized successfully!");
+    log::info!("Run 'unibook build' to build your book.");
 
     Ok(())
 }
@@ -203,7 +491,11 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
     use std::time::Duration;
 
     // First, build the book
-    build_book(dir)?;
+    keep_serving(build_book(
+        dir,
+        builder::OutputFormat::Html,
+        BuildOptions::preview(),
+    ))?;
 
     // Load config to get output directory
     let config_path = dir.join("book.toml");
@@ -217,9 +509,9 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
             .map_err(|e| anyhow::anyhow!("Failed to start server on {}: {}", addr, e))?,
     );
 
-    println!("\nServing book at http://localhost:{}/", port);
-    println!("Watching for changes in {}...", src_dir.display());
-    println!("Press Ctrl+C to stop\n");
+    log::info!("\nServing book at http://localhost:{}/", port);
+    log::info!("Watching for changes in {}...", src_dir.display());
+    log::info!("Press Ctrl+C to stop\n");
 
     // Setup file watcher
     let (watch_tx, watch_rx) = channel();
@@ -244,7 +536,9 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
             match watch_rx.recv_timeout(Duration::from_millis(100)) {
                 Ok(event) => {
                     if last_build.elapsed() > Duration::from_millis(500) {
-                        println!("\n[Watch] Change detected: {:?}", event);
+                        log::event(log::Event::RebuildTriggered {
+                            paths: &event.paths,
+                        });
 
                         // Determine which file changed
                         let changed_file = event.paths.first();
@@ -253,23 +547,25 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
                         let config_path = dir_clone.join("book.toml");
                         match config::Config::from_file(&config_path) {
                             Ok(cfg) => match book::Book::from_config(cfg, &dir_clone) {
-                                Ok(bk) => match builder::Builder::new(bk, &dir_clone) {
+                                Ok(bk) => match builder::Builder::new(bk, &dir_clone)
+                                    .map(|builder| builder.keep_going(true))
+                                {
                                     Ok(builder) => {
-                                        println!("[Watch] Rebuilding...");
+                                        log::info!("[Watch] Rebuilding...");
                                         let result = builder
                                             .build_incremental(changed_file.map(|p| p.as_path()));
                                         match result {
-                                            Ok(_) => println!("[Watch] Build successful!\n"),
-                                            Err(e) => eprintln!("[Watch] Build failed: {}\n", e),
+                                            Ok(_) => log::info!("[Watch] Build successful!\n"),
+                                            Err(e) => log::error!("[Watch] Build failed: {}\n", e),
                                         }
                                     }
                                     Err(e) => {
-                                        eprintln!("[Watch] Failed to create builder: {}\n", e)
+                                        log::error!("[Watch] Failed to create builder: {}\n", e)
                                     }
                                 },
-                                Err(e) => eprintln!("[Watch] Failed to load book: {}\n", e),
+                                Err(e) => log::error!("[Watch] Failed to load book: {}\n", e),
                             },
-                            Err(e) => eprintln!("[Watch] Failed to load config: {}\n", e),
+                            Err(e) => log::error!("[Watch] Failed to load config: {}\n", e),
                         }
                         last_build = std::time::Instant::now();
                     }
@@ -308,6 +604,10 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
                             "text/css"
                         } else if file_path.extension().and_then(|s| s.to_str()) == Some("js") {
                             "application/javascript"
+                        } else if file_path.extension().and_then(|s| s.to_str()) == Some("md") {
+                            "text/markdown; charset=utf-8"
+                        } else if file_path.extension().and_then(|s| s.to_str()) == Some("txt") {
+                            "text/plain; charset=utf-8"
                         } else {
                             "application/octet-stream"
                         };
@@ -328,7 +628,19 @@ fn serve_book(dir: &Path, port: u16) -> Result<()> {
                 }
             }
         } else {
-            let response = tiny_http::Response::from_string("404 Not Found").with_status_code(404);
+            let not_found_path = output_dir.join("404.html");
+            let response = match std::fs::read(&not_found_path) {
+                Ok(content) => tiny_http::Response::from_data(content)
+                    .with_status_code(404)
+                    .with_header(
+                        tiny_http::Header::from_bytes(
+                            &b"Content-Type"[..],
+                            &b"text/html; charset=utf-8"[..],
+                        )
+                        .unwrap(),
+                    ),
+                Err(_) => tiny_http::Response::from_string("404 Not Found").with_status_code(404),
+            };
             let _ = request.respond(response);
         }
     }
@@ -350,14 +662,18 @@ fn watch_book(dir: &Path, dev_mode: bool) -> Result<()> {
     let src_dir = dir.join(&config.build.src_dir);
 
     // Initial build
-    println!("Initial build...");
-    build_book(dir)?;
-
-    println!("\nWatching for changes in {}...", src_dir.display());
+    log::info!("Initial build...");
+    keep_serving(build_book(
+        dir,
+        builder::OutputFormat::Html,
+        BuildOptions::preview(),
+    ))?;
+
+    log::info!("\nWatching for changes in {}...", src_dir.display());
     if dev_mode {
-        println!("Dev mode: Also watching unibook source for changes");
+        log::info!("Dev mode: Also watching unibook source for changes");
     }
-    println!("Press Ctrl+C to stop");
+    log::info!("Press Ctrl+C to stop");
 
     let (tx, rx) = channel();
 
@@ -383,7 +699,7 @@ fn watch_book(dir: &Path, dev_mode: bool) -> Result<()> {
             let unibook_cargo = current_dir.join("Cargo.toml");
 
             if unibook_src_dir.exists() && unibook_cargo.exists() {
-                println!("Watching unibook source: {}", unibook_src_dir.display());
+                log::info!("Watching unibook source: {}", unibook_src_dir.display());
                 watcher.watch(&unibook_src_dir, RecursiveMode::Recursive)?;
             }
         }
@@ -411,26 +727,32 @@ fn watch_book(dir: &Path, dev_mode: bool) -> Result<()> {
                 if is_unibook_source {
                     // Debounce: only recompile if 1s has passed since last compile
                     if last_compile.elapsed() > Duration::from_millis(1000) {
-                        println!("\n[DEV] Unibook source changed, recompiling...");
+                        log::info!("\n[DEV] Unibook source changed, recompiling...");
 
                         let output = std::process::Command::new("cargo").arg("build").output();
 
                         match output {
                             Ok(out) if out.status.success() => {
-                                println!("[DEV] Recompile successful!");
+                                log::info!("[DEV] Recompile successful!");
                                 // Also rebuild the book
-                                println!("Rebuilding book...");
-                                match build_book(dir) {
-                                    Ok(_) => println!("Build successful!"),
-                                    Err(e) => eprintln!("Build failed: {}", e),
+                                log::info!("Rebuilding book...");
+                                match build_book(
+                                    dir,
+                                    builder::OutputFormat::Html,
+                                    BuildOptions::preview(),
+                                ) {
+                                    Ok(_) => log::info!("Build successful!"),
+                                    Err(e) => log::error!("Build failed: {}", e),
                                 }
                             }
                             Ok(out) => {
-                                eprintln!("[DEV] Recompile failed:");
-                                eprintln!("{}", String::from_utf8_lossy(&out.stderr));
+                                log::error!(
+                                    "[DEV] Recompile failed:\n{}",
+                                    String::from_utf8_lossy(&out.stderr)
+                                );
                             }
                             Err(e) => {
-                                eprintln!("[DEV] Failed to run cargo: {}", e);
+                                log::error!("[DEV] Failed to run cargo: {}", e);
                             }
                         }
                         last_compile = std::time::Instant::now();
@@ -440,7 +762,9 @@ fn watch_book(dir: &Path, dev_mode: bool) -> Result<()> {
                     // Regular book rebuild
                     // Debounce: only rebuild if 500ms have passed since last build
                     if last_build.elapsed() > Duration::from_millis(500) {
-                        println!("\nChange detected: {:?}", event);
+                        log::event(log::Event::RebuildTriggered {
+                            paths: &event.paths,
+                        });
 
                         // Determine which file changed
                         let changed_file = event.paths.first();
@@ -449,21 +773,23 @@ fn watch_book(dir: &Path, dev_mode: bool) -> Result<()> {
                         let config_path = dir.join("book.toml");
                         match config::Config::from_file(&config_path) {
                             Ok(cfg) => match book::Book::from_config(cfg, dir) {
-                                Ok(bk) => match builder::Builder::new(bk, dir) {
+                                Ok(bk) => match builder::Builder::new(bk, dir)
+                                    .map(|builder| builder.keep_going(true))
+                                {
                                     Ok(builder) => {
-                                        println!("Rebuilding...");
+                                        log::info!("Rebuilding...");
                                         let result = builder
                                             .build_incremental(changed_file.map(|p| p.as_path()));
                                         match result {
-                                            Ok(_) => println!("Build successful!"),
-                                            Err(e) => eprintln!("Build failed: {}", e),
+                                            Ok(_) => log::info!("Build successful!"),
+                                            Err(e) => log::error!("Build failed: {}", e),
                                         }
                                     }
-                                    Err(e) => eprintln!("Failed to create builder: {}", e),
+                                    Err(e) => log::error!("Failed to create builder: {}", e),
                                 },
-                                Err(e) => eprintln!("Failed to load book: {}", e),
+                                Err(e) => log::error!("Failed to load book: {}", e),
                             },
-                            Err(e) => eprintln!("Failed to load config: {}", e),
+                            Err(e) => log::error!("Failed to load config: {}", e),
                         }
                         last_build = std::time::Instant::now();
                     }
@@ -0,0 +1,326 @@
+use crate::book::{Book, PageInfo};
+use crate::toc::attr_escape;
+use std::collections::HashMap;
+
+/// Max length of a description derived from the first paragraph
+const DESCRIPTION_MAX_CHARS: usize = 160;
+
+/// Simple `key: value` front matter at the top of a markdown file
+///
+/// ```text
+/// ---
+/// description: A short summary
+/// image: images/cover.png
+/// ---
+/// ```
+#[derive(Debug, Default)]
+pub struct FrontMatter {
+    values: HashMap<String, String>,
+}
+
+impl FrontMatter {
+    /// Parse front matter and return it with the remaining body
+    pub fn parse(content: &str) -> (Self, &str) {
+        let mut values = HashMap::new();
+
+        let Some(rest) = content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+        else {
+            return (Self { values }, content);
+        };
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            let line = line.trim_end();
+            if line == "---" || line == "..." {
+                for (key, value) in Self::parse_lines(&rest[..offset - line.len()]) {
+                    values.insert(key, value);
+                }
+                return (Self { values }, &rest[offset..]);
+            }
+        }
+
+        // No closing delimiter: not a front matter
+        (Self { values }, content)
+    }
+
+    fn parse_lines(block: &str) -> Vec<(String, String)> {
+        block
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                let key = key.trim();
+                if key.is_empty() || key.starts_with('#') || line.starts_with(char::is_whitespace) {
+                    return None;
+                }
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                    .unwrap_or(value);
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(key)
+            .map(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// First paragraph of markdown as plain text (front matter already removed)
+pub fn first_paragraph(body: &str) -> Option<String> {
+    let mut paragraph = Vec::new();
+    let mut in_code_block = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if trimmed.is_empty() {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        // Skip headings, lists, quotes, tables, HTML, images and math blocks
+        let is_block_syntax = ["#", "-", "*", "+", ">", "|", "<", "![", "$$"]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+            || trimmed
+                .split_once(". ")
+                .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        if is_block_syntax {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(trimmed);
+    }
+
+    if paragraph.is_empty() {
+        return None;
+    }
+    Some(truncate(&strip_inline_markdown(&paragraph.join(" "))))
+}
+
+/// Remove inline markdown: links keep their text, emphasis and code marks are dropped
+fn strip_inline_markdown(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '[' => {
+                // [text](url) -> text
+                let mut label = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    label.push(c);
+                }
+                result.push_str(&label);
+                if closed && chars.peek() == Some(&'(') {
+                    for c in chars.by_ref() {
+                        if c == ')' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '*' | '`' => {}
+            _ => result.push(ch),
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= DESCRIPTION_MAX_CHARS {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(DESCRIPTION_MAX_CHARS - 1).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+pub struct MetaTags;
+
+impl MetaTags {
+    /// `<meta>` tags, OpenGraph, Twitter card and JSON-LD for a page
+    /// Page-level values (front matter, first paragraph) take priority over book config
+    pub fn generate(book: &Book, page: &PageInfo) -> String {
+        let content = std::fs::read_to_string(&page.source_path).unwrap_or_default();
+        let (front_matter, body) = FrontMatter::parse(&content);
+
+        let description = front_matter
+            .get("description")
+            .map(|d| d.to_string())
+            .or_else(|| first_paragraph(body))
+            .or_else(|| book.config.book.description.clone());
+        let image = front_matter
+            .get("image")
+            .map(|image| Self::resolve_image(book, page, image))
+            .or_else(|| {
+                book.config
+                    .book
+                    .image
+                    .as_deref()
+                    .map(|image| Self::resolve_image(book, page, image))
+            });
+        let authors = &book.config.book.authors;
+        let url = book.absolute_url(&page.output_filename);
+
+        let mut html = String::new();
+        let mut meta = |attr: &str, key: &str, value: &str| {
+            html.push_str(&format!(
+                "<meta {}=\"{}\" content=\"{}\">\n",
+                attr,
+                key,
+                attr_escape(value)
+            ));
+        };
+
+        if let Some(description) = &description {
+            meta("name", "description", description);
+        }
+        if !authors.is_empty() {
+            meta("name", "author", &authors.join(", "));
+        }
+        meta("property", "og:type", "article");
+        meta("property", "og:title", &page.title);
+        meta("property", "og:site_name", &book.config.book.title);
+        if let Some(description) = &description {
+            meta("property", "og:description", description);
+        }
+        if let Some(url) = &url {
+            meta("property", "og:url", url);
+        }
+        if let Some(image) = &image {
+            meta("property", "og:image", image);
+        }
+        let card = if image.is_some() {
+            "summary_large_image"
+        } else {
+            "summary"
+        };
+        meta("name", "twitter:card", card);
+        meta("name", "twitter:title", &page.title);
+        if let Some(description) = &description {
+            meta("name", "twitter:description", description);
+        }
+        if let Some(image) = &image {
+            meta("name", "twitter:image", image);
+        }
+
+        let mut json_ld = serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "TechArticle",
+            "headline": page.title,
+            "inLanguage": book.config.book.language,
+            "isPartOf": {
+                "@type": "Book",
+                "name": book.config.book.title,
+            },
+        });
+        if let Some(description) = &description {
+            json_ld["description"] = description.clone().into();
+        }
+        if !authors.is_empty() {
+            json_ld["author"] = authors
+                .iter()
+                .map(|name| serde_json::json!({ "@type": "Person", "name": name }))
+                .collect();
+        }
+        if let Some(url) = &url {
+            json_ld["url"] = url.clone().into();
+        }
+        if let Some(image) = &image {
+            json_ld["image"] = image.clone().into();
+        }
+        html.push_str(&format!(
+            "<script type=\"application/ld+json\">{}</script>\n",
+            json_ld.to_string().replace("</", "<\\/")
+        ));
+
+        html
+    }
+
+    /// Make an image path absolute (site_url when configured, otherwise base_path)
+    /// Relative paths are resolved against the page's directory
+    fn resolve_image(book: &Book, page: &PageInfo, image: &str) -> String {
+        if image.contains("://") {
+            return image.to_string();
+        }
+        let path = match image.strip_prefix('/') {
+            Some(root_relative) => root_relative.to_string(),
+            None => match page.output_filename.rsplit_once('/') {
+                Some((dir, _)) => format!("{}/{}", dir, image),
+                None => image.to_string(),
+            },
+        };
+        book.absolute_url(&path).unwrap_or_else(|| {
+            format!(
+                "{}/{}",
+                crate::toc::normalize_base_path(&book.config.build.base_path),
+                path
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_matter_parse() {
+        let content = "---\ndescription: \"Hello: world\"\nimage: cover.png\n---\n# Title\n";
+        let (front_matter, body) = FrontMatter::parse(content);
+        assert_eq!(front_matter.get("description"), Some("Hello: world"));
+        assert_eq!(front_matter.get("image"), Some("cover.png"));
+        assert_eq!(body, "# Title\n");
+    }
+
+    #[test]
+    fn test_front_matter_absent() {
+        let content = "# Title\n\n---\n";
+        let (front_matter, body) = FrontMatter::parse(content);
+        assert_eq!(front_matter.get("description"), None);
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_first_paragraph() {
+        let body =
+            "# Title\n\n```\ncode\n```\n\nThis is **the** [first](a.html)\nparagraph.\n\nSecond.";
+        assert_eq!(
+            first_paragraph(body),
+            Some("This is the first paragraph.".to_string())
+        );
+        assert_eq!(first_paragraph("# Only heading\n- item\n"), None);
+    }
+
+    #[test]
+    fn test_first_paragraph_truncated() {
+        let body = "あ".repeat(200);
+        let description = first_paragraph(&body).unwrap();
+        assert_eq!(description.chars().count(), DESCRIPTION_MAX_CHARS);
+        assert!(description.ends_with('…'));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::book::PageInfo;
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct NotFoundData<'a> {
+    base_path: &'a str,
+    pages: Vec<NotFoundEntry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct NotFoundEntry<'a> {
+    title: &'a str,
+    path: &'a str,
+}
+
+pub struct NotFoundPage;
+
+impl NotFoundPage {
+    /// Markdown body of 404.html
+    /// The suggestion list is filled in by not-found.js
+    pub fn markdown() -> &'static str {
+        r#"# Page not found
+
+The page you are looking for does not exist.
+
+Did you mean:
+
+<ul id="not-found-suggestions"></ul>
+"#
+    }
+
+    /// `<base>` tag so that relative URLs (e.g. search-index.json) resolve
+    /// against the book root regardless of the requested depth
+    pub fn base_tag(base_path: &str) -> String {
+        format!(r#"<base href="{}/">"#, base_path)
+    }
+
+    /// Page list and not-found.js, included after the body
+    pub fn script(pages: &[&PageInfo], base_path: &str) -> Result<String> {
+        let data = NotFoundData {
+            base_path,
+            pages: pages
+                .iter()
+                .map(|page| NotFoundEntry {
+                    title: &page.title,
+                    path: &page.output_filename,
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string(&data).context("Failed to serialize page list")?;
+        // Prevent "</script>" inside titles from closing the script element
+        let json = json.replace("</", "<\\/");
+
+        Ok(format!(
+            "<script>window.UNIBOOK_NOT_FOUND = {};</script>\n<script>{}</script>",
+            json,
+            crate::search_assets::SearchAssets::not_found_js()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_script_contains_pages() {
+        let page = PageInfo {
+            title: "Intro </script>".to_string(),
+            source_path: PathBuf::from("src/intro.md"),
+            output_filename: "a/intro.html".to_string(),
+            url: "/a/intro.html".to_string(),
+            sections: vec![],
+            word_count: 0,
+        };
+        let script = NotFoundPage::script(&[&page], "/book").unwrap();
+
+        assert!(script.contains(r#""base_path":"/book""#));
+        assert!(script.contains(r#""path":"a/intro.html""#));
+        assert!(!script.contains("Intro </script>"));
+    }
+
+    #[test]
+    fn test_base_tag() {
+        assert_eq!(NotFoundPage::base_tag("/book"), r#"<base href="/book/">"#);
+        assert_eq!(NotFoundPage::base_tag(""), r#"<base href="/">"#);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::book::Book;
+use crate::html::{percent_decode, resolve_relative};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A file under src_dir that the book never uses
+#[derive(Debug, PartialEq)]
+pub enum Orphan {
+    /// .md file not referenced by any page in book.toml (nor included by one)
+    Page(PathBuf),
+    /// Other file not referenced by any page
+    Asset(PathBuf),
+}
+
+impl fmt::Display for Orphan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Orphan::Page(path) => write!(f, "{} is not referenced in book.toml", path.display()),
+            Orphan::Asset(path) => write!(f, "{} is not referenced by any page", path.display()),
+        }
+    }
+}
+
+/// Find .md files and assets under src_dir that no page references
+pub fn find_orphans(book: &Book, base_dir: &Path) -> Result<Vec<Orphan>> {
+    let src_dir = base_dir.join(&book.config.build.src_dir);
+    let base_path = crate::toc::normalize_base_path(&book.config.build.base_path);
+
+    // src_dir-relative paths of pages, included files and referenced assets
+    let mut used = HashSet::new();
+    let mut queue: Vec<String> = crate::builder::Builder::collect_pages(&book.items)
+        .iter()
+        .filter_map(|page| relative_path(&page.source_path, &src_dir))
+        .collect();
+
+    while let Some(path) = queue.pop() {
+        if !used.insert(path.clone()) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(src_dir.join(&path)) else {
+            continue;
+        };
+        let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+        for (url, is_include) in references(&content) {
+            let url = url.split(['#', '?']).next().unwrap_or_default();
+            if url.is_empty() || crate::html::is_external(url) {
+                continue;
+            }
+            let target = match url.strip_prefix('/') {
+                Some(absolute) => absolute
+                    .strip_prefix(base_path.trim_start_matches('/'))
+                    .map(|p| p.trim_start_matches('/'))
+                    .unwrap_or(absolute)
+                    .to_string(),
+                None => resolve_relative(dir, url),
+            };
+            let target = percent_decode(&target);
+            if is_include {
+                // Included files are scanned for their own references
+                queue.push(target);
+            } else if !target.ends_with(".md") {
+                used.insert(target);
+            }
+        }
+    }
+
+    // Generated and config files, for books with src_dir = "."
+    let skip = [
+        book.output_dir(base_dir),
+        base_dir.join("book.toml"),
+        base_dir.join(&book.config.check.cache_file),
+    ];
+    let mut files = Vec::new();
+    collect_files(&src_dir, &skip, &mut files)
+        .context(format!("Failed to list {}", src_dir.display()))?;
+    files.sort();
+
+    Ok(files
+        .into_iter()
+        .filter_map(|file| {
+            let relative = relative_path(&file, &src_dir)?;
+            if used.contains(&relative) {
+                return None;
+            }
+            // "./a.md" -> "a.md" when src_dir is "."
+            let path: PathBuf = Path::new(&book.config.build.src_dir)
+                .join(&relative)
+                .components()
+                .filter(|c| *c != Component::CurDir)
+                .collect();
+            Some(if relative.ends_with(".md") {
+                Orphan::Page(path)
+            } else {
+                Orphan::Asset(path)
+            })
+        })
+        .collect())
+}
+
+/// "src/a/b.md" relative to "src" -> "a/b.md"
+fn relative_path(path: &Path, src_dir: &Path) -> Option<String> {
+    let relative = path.strip_prefix(src_dir).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// Files under `dir`, skipping hidden files and directories and `skip`
+fn collect_files(dir: &Path, skip: &[PathBuf], files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            || skip.contains(&path)
+        {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, skip, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// URLs in Markdown links/images and HTML src/href attributes,
+/// with whether the line is an `@[include](path)` directive
+fn references(markdown: &str) -> Vec<(String, bool)> {
+    let mut urls = Vec::new();
+    for line in markdown.lines() {
+        let is_include = line.trim_start().starts_with("@[include](");
+        let mut rest = line;
+        while let Some(pos) = rest.find("](") {
+            rest = &rest[pos + 2..];
+            let end = rest.find(')').unwrap_or(rest.len());
+            // Drop an optional title: ![alt](img.png "Title")
+            let target = rest[..end].split_whitespace().next().unwrap_or_default();
+            let target = target.trim_start_matches('<').trim_end_matches('>');
+            urls.push((target.to_string(), is_include));
+            rest = &rest[end..];
+        }
+        for attr in ["src", "href"] {
+            crate::html::rewrite_attribute(line, attr, |value| {
+                urls.push((value.to_string(), false));
+                value.to_string()
+            });
+        }
+    }
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::{BookItem, PageInfo};
+    use crate::config::Config;
+
+    #[test]
+    fn test_find_orphans() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-orphans");
+        let src_dir = temp_dir.join("src");
+        fs::create_dir_all(src_dir.join("guide/img")).unwrap();
+        fs::create_dir_all(src_dir.join(".hidden")).unwrap();
+
+        fs::write(
+            src_dir.join("guide/setup.md"),
+            "# Setup\n![diagram](img/flow.png \"Flow\")\n<img src=\"/book/logo.svg\">\n@[include](parts/common.md)\n",
+        )
+        .unwrap();
+        fs::create_dir_all(src_dir.join("guide/parts")).unwrap();
+        fs::write(
+            src_dir.join("guide/parts/common.md"),
+            "![](../img/shared%20icon.png)\n",
+        )
+        .unwrap();
+        fs::write(src_dir.join("guide/img/flow.png"), "").unwrap();
+        fs::write(src_dir.join("guide/img/shared icon.png"), "").unwrap();
+        fs::write(src_dir.join("guide/img/unused.png"), "").unwrap();
+        fs::write(src_dir.join("logo.svg"), "").unwrap();
+        fs::write(src_dir.join("old.md"), "# Old\n").unwrap();
+        fs::write(src_dir.join(".hidden/x.md"), "").unwrap();
+
+        let config: Config = toml::from_str(
+            "[book]\ntitle = \"Test\"\n[build]\nbase_path = \"/book\"\n[[pages]]\ntitle = \"Setup\"\npath = \"guide/setup.md\"\n",
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![BookItem::Page(PageInfo {
+                title: "Setup".to_string(),
+                source_path: src_dir.join("guide/setup.md"),
+                output_filename: "guide/setup.html".to_string(),
+                url: "/book/guide/setup.html".to_string(),
+                sections: vec![],
+                word_count: 0,
+            })],
+        };
+
+        let orphans = find_orphans(&book, &temp_dir).unwrap();
+        assert_eq!(
+            orphans,
+            vec![
+                Orphan::Asset(PathBuf::from("src/guide/img/unused.png")),
+                Orphan::Page(PathBuf::from("src/old.md")),
+            ]
+        );
+        assert_eq!(
+            orphans[1].to_string(),
+            "src/old.md is not referenced in book.toml"
+        );
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_find_orphans_in_book_root() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-orphans-root");
+        fs::create_dir_all(temp_dir.join("site")).unwrap();
+        fs::write(temp_dir.join("book.toml"), "").unwrap();
+        fs::write(temp_dir.join("intro.md"), "# Intro\n").unwrap();
+        fs::write(temp_dir.join("links.json"), "{}").unwrap();
+        fs::write(temp_dir.join("site/intro.html"), "").unwrap();
+        fs::write(temp_dir.join("site/intro.md"), "").unwrap();
+        fs::write(temp_dir.join("notes.md"), "").unwrap();
+
+        let config: Config = toml::from_str(
+            "[book]\ntitle = \"Test\"\n[build]\nsrc_dir = \".\"\noutput_dir = \"site\"\n[check]\ncache_file = \"links.json\"\n[[pages]]\ntitle = \"Intro\"\npath = \"intro.md\"\n",
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![BookItem::Page(PageInfo {
+                title: "Intro".to_string(),
+                source_path: temp_dir.join("./intro.md"),
+                output_filename: "intro.html".to_string(),
+                url: "/intro.html".to_string(),
+                sections: vec![],
+                word_count: 0,
+            })],
+        };
+
+        let orphans = find_orphans(&book, &temp_dir).unwrap();
+        assert_eq!(orphans, vec![Orphan::Page(PathBuf::from("notes.md"))]);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+}
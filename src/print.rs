@@ -0,0 +1,250 @@
+use crate::book::{BookItem, PageInfo};
+use crate::html::{is_external, resolve_relative, rewrite_attribute};
+use crate::toc::{attr_escape, html_escape};
+use std::collections::HashMap;
+
+pub struct PrintPage;
+
+impl PrintPage {
+    /// Button in page controls linking to print.html
+    pub fn control_html(base_path: &str) -> String {
+        format!(
+            r#"<a id="print-link" class="page-control-link" href="{}/print.html" title="Print this book">
+  <svg width="16" height="16" viewBox="0 0 16 16" fill="currentColor">
+    <path d="M4 1h8v4H4V1zm-2 5h12a2 2 0 0 1 2 2v4h-3v3H3v-3H0V8a2 2 0 0 1 2-2zm2 5v3h8v-3H4zm9-3a1 1 0 1 0 0 2 1 1 0 0 0 0-2z"/>
+  </svg>
+</a>
+"#,
+            base_path
+        )
+    }
+
+    /// Element id of a page's section in print.html: `/` becomes `-`, and
+    /// `_` and `-` are escaped as `__` and `_-` so that `a/b.html` and
+    /// `a-b.html` get different ids
+    pub fn page_anchor(output_filename: &str) -> String {
+        output_filename
+            .trim_end_matches(".html")
+            .replace('_', "__")
+            .replace('-', "_-")
+            .replace(['/', '\\'], "-")
+    }
+
+    /// Assemble the print.html body from rendered page fragments in TOC order
+    /// `fragments` maps output_filename to the page's rendered HTML (body only)
+    pub fn assemble(items: &[BookItem], fragments: &HashMap<String, String>) -> String {
+        let mut html = String::new();
+        html.push_str("<div class=\"print-book\">\n");
+        Self::assemble_items(&mut html, items, fragments, 1);
+        html.push_str("</div>\n");
+        html
+    }
+
+    fn assemble_items(
+        html: &mut String,
+        items: &[BookItem],
+        fragments: &HashMap<String, String>,
+        level: u8,
+    ) {
+        for item in items {
+            match item {
+                BookItem::Part { title, children } => {
+                    html.push_str(&format!(
+                        "<h1 class=\"print-part print-part-{}\">{}</h1>\n",
+                        level,
+                        html_escape(title)
+                    ));
+                    Self::assemble_items(html, children, fragments, level + 1);
+                }
+                BookItem::Page(page) => {
+                    if let Some(fragment) = fragments.get(&page.output_filename) {
+                        html.push_str(&format!(
+                            "<section class=\"print-chapter\" id=\"{}\">\n{}\n</section>\n",
+                            attr_escape(&Self::page_anchor(&page.output_filename)),
+                            fragment
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rewrite a page fragment so that it can live inside print.html
+    /// - ids are prefixed with the page anchor to avoid collisions
+    /// - links to pages in the book become in-document links
+    /// - other relative URLs are made relative to the output root
+    pub fn rewrite_fragment(
+        html: &str,
+        page: &PageInfo,
+        pages: &[&PageInfo],
+        base_path: &str,
+    ) -> String {
+        let anchor = Self::page_anchor(&page.output_filename);
+        let page_dir = page.output_dir();
+
+        let html = rewrite_attribute(html, "id", |id| format!("{}--{}", anchor, id));
+        let html = Self::rewrite_links(&html, &page_dir, Some(&anchor), pages, base_path);
+        rewrite_attribute(&html, "src", |src| {
+            if is_external(src) || src.starts_with('/') {
+                src.to_string()
+            } else {
+                resolve_relative(&page_dir, src)
+            }
+        })
+    }
+
+    /// Rewrite hrefs to pages in the book into in-document links
+    /// `page_dir` is the directory relative hrefs are resolved against, and
+    /// `current_anchor` the page that bare "#fragment" links belong to
+    pub fn rewrite_links(
+        html: &str,
+        page_dir: &str,
+        current_anchor: Option<&str>,
+        pages: &[&PageInfo],
+        base_path: &str,
+    ) -> String {
+        let known: HashMap<&str, String> = pages
+            .iter()
+            .map(|p| {
+                (
+                    p.output_filename.as_str(),
+                    Self::page_anchor(&p.output_filename),
+                )
+            })
+            .collect();
+
+        rewrite_attribute(html, "href", |href| {
+            if let Some(fragment) = href.strip_prefix('#') {
+                return match current_anchor {
+                    Some(anchor) => Self::in_document_link(anchor, fragment),
+                    None => href.to_string(),
+                };
+            }
+            if is_external(href) {
+                return href.to_string();
+            }
+            let (path, fragment) = match href.split_once('#') {
+                Some((path, fragment)) => (path, fragment),
+                None => (href, ""),
+            };
+            let resolved = match path.strip_prefix('/') {
+                Some(absolute) => absolute
+                    .strip_prefix(base_path.trim_start_matches('/'))
+                    .map(|p| p.trim_start_matches('/'))
+                    .unwrap_or(absolute)
+                    .to_string(),
+                None => resolve_relative(page_dir, path),
+            };
+            let resolved = match resolved.strip_suffix(".md") {
+                Some(stem) => format!("{}.html", stem),
+                None => resolved,
+            };
+            match known.get(resolved.as_str()) {
+                Some(target) => Self::in_document_link(target, fragment),
+                None if path.starts_with('/') => href.to_string(),
+                None => {
+                    let mut url = resolve_relative(page_dir, path);
+                    if !fragment.is_empty() {
+                        url.push('#');
+                        url.push_str(fragment);
+                    }
+                    url
+                }
+            }
+        })
+    }
+
+    /// In-document URL of a page (and optional fragment) inside print.html
+    pub fn in_document_link(anchor: &str, fragment: &str) -> String {
+        if fragment.is_empty() {
+            format!("#{}", anchor)
+        } else {
+            format!("#{}--{}", anchor, fragment)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn page(output_filename: &str) -> PageInfo {
+        PageInfo {
+            title: output_filename.to_string(),
+            source_path: PathBuf::from(output_filename.replace(".html", ".md")),
+            output_filename: output_filename.to_string(),
+            url: format!("/{}", output_filename),
+            sections: vec![],
+            word_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_fragment_ids_and_links() {
+        let intro = page("intro.html");
+        let nested = page("guide/setup.html");
+        let pages = vec![&intro, &nested];
+
+        let html = r##"<h2 id="2-Usage">Usage</h2>
+<a href="#2-Usage">self</a>
+<a href="guide/setup.html#2-Install">setup</a>
+<a href="/book/intro.html">abs</a>
+<a href="https://example.com/x.html">ext</a>
+<img src="images/a.png">"##;
+        let out = PrintPage::rewrite_fragment(html, &intro, &pages, "/book");
+
+        assert!(out.contains(r#"id="intro--2-Usage""#));
+        assert!(out.contains(r##"href="#intro--2-Usage""##));
+        assert!(out.contains(r##"href="#guide-setup--2-Install""##));
+        assert!(out.contains(r##"href="#intro""##));
+        assert!(out.contains(r#"href="https://example.com/x.html""#));
+        assert!(out.contains(r#"src="images/a.png""#));
+    }
+
+    #[test]
+    fn test_page_anchor() {
+        assert_eq!(PrintPage::page_anchor("guide/setup.html"), "guide-setup");
+        assert_eq!(PrintPage::page_anchor("a-b.html"), "a_-b");
+        assert_eq!(PrintPage::page_anchor("a_b.html"), "a__b");
+        assert_ne!(
+            PrintPage::page_anchor("a/b.html"),
+            PrintPage::page_anchor("a-b.html")
+        );
+        assert_ne!(
+            PrintPage::page_anchor("a_/b.html"),
+            PrintPage::page_anchor("a/_b.html")
+        );
+    }
+
+    #[test]
+    fn test_rewrite_fragment_nested_page() {
+        let intro = page("intro.html");
+        let nested = page("guide/setup.html");
+        let pages = vec![&intro, &nested];
+
+        let html = r#"<a href="../intro.md">back</a><a data-id="x" href="notes.txt">n</a><img src="img.png">"#;
+        let out = PrintPage::rewrite_fragment(html, &nested, &pages, "");
+
+        assert!(out.contains(r##"href="#intro""##));
+        assert!(out.contains(r#"data-id="x""#));
+        assert!(out.contains(r#"href="guide/notes.txt""#));
+        assert!(out.contains(r#"src="guide/img.png""#));
+    }
+
+    #[test]
+    fn test_assemble_with_parts() {
+        let intro = page("intro.html");
+        let items = vec![BookItem::Part {
+            title: "Part <1>".to_string(),
+            children: vec![BookItem::Page(intro)],
+        }];
+        let mut fragments = HashMap::new();
+        fragments.insert("intro.html".to_string(), "<p>Hello</p>".to_string());
+
+        let html = PrintPage::assemble(&items, &fragments);
+        assert!(html.contains("<h1 class=\"print-part print-part-1\">Part &lt;1&gt;</h1>"));
+        assert!(html.contains("<section class=\"print-chapter\" id=\"intro\">"));
+        assert!(html.contains("<p>Hello</p>"));
+    }
+}
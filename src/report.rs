@@ -0,0 +1,247 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Pages listed in the slowest / largest summary
+const SUMMARY_PAGES: usize = 5;
+
+/// Per-page statistics collected while building
+#[derive(Debug, Clone, Serialize)]
+pub struct PageReport {
+    pub title: String,
+    pub source_path: PathBuf,
+    pub output_filename: String,
+    /// Wall time of the unidoc run
+    pub unidoc_ms: u64,
+    /// Size of the generated HTML file
+    pub output_bytes: u64,
+    /// Size of the CSS/JS/HTML includes inlined into the page (excluding the TOC)
+    pub inlined_bytes: u64,
+    /// Size of the TOC inlined into the page
+    pub toc_bytes: u64,
+    pub word_count: usize,
+    /// Lines unidoc printed to stderr
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportTotals {
+    pub pages: usize,
+    /// Duration of the whole build
+    pub duration_ms: u64,
+    pub unidoc_ms: u64,
+    pub output_bytes: u64,
+    pub search_index_bytes: u64,
+    pub word_count: usize,
+    pub warnings: usize,
+}
+
+/// `unibook build --report report.json`
+#[derive(Debug, Serialize)]
+pub struct BuildReport {
+    pub pages: Vec<PageReport>,
+    pub totals: ReportTotals,
+}
+
+impl BuildReport {
+    pub fn new(pages: Vec<PageReport>, duration: Duration, search_index_bytes: u64) -> Self {
+        let totals = ReportTotals {
+            pages: pages.len(),
+            duration_ms: duration.as_millis() as u64,
+            unidoc_ms: pages.iter().map(|p| p.unidoc_ms).sum(),
+            output_bytes: pages.iter().map(|p| p.output_bytes).sum(),
+            search_index_bytes,
+            word_count: pages.iter().map(|p| p.word_count).sum(),
+            warnings: pages.iter().map(|p| p.warnings.len()).sum(),
+        };
+        Self { pages, totals }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize build report")?;
+        std::fs::write(path, json)
+            .context(format!("Failed to write build report: {}", path.display()))
+    }
+
+    /// Human-readable totals plus the slowest and largest pages
+    pub fn summary(&self) -> String {
+        let totals = &self.totals;
+        let mut text = format!(
+            "Build report: {} pages in {} ms (unidoc {} ms), {} output, search index {}, {} warning(s)\n",
+            totals.pages,
+            totals.duration_ms,
+            totals.unidoc_ms,
+            format_size(totals.output_bytes),
+            format_size(totals.search_index_bytes),
+            totals.warnings
+        );
+
+        let mut slowest: Vec<&PageReport> = self.pages.iter().collect();
+        slowest.sort_by_key(|page| std::cmp::Reverse(page.unidoc_ms));
+        text.push_str("Slowest pages:\n");
+        for page in slowest.iter().take(SUMMARY_PAGES) {
+            text.push_str(&format!(
+                "  {:>6} ms  {}\n",
+                page.unidoc_ms, page.output_filename
+            ));
+        }
+
+        let mut largest: Vec<&PageReport> = self.pages.iter().collect();
+        largest.sort_by_key(|page| std::cmp::Reverse(page.output_bytes));
+        text.push_str("Largest pages:\n");
+        for page in largest.iter().take(SUMMARY_PAGES) {
+            text.push_str(&format!(
+                "  {:>9}  {} (inlined {}, TOC {})\n",
+                format_size(page.output_bytes),
+                page.output_filename,
+                format_size(page.inlined_bytes),
+                format_size(page.toc_bytes)
+            ));
+        }
+        text
+    }
+}
+
+/// A page that failed to build under `--keep-going`
+#[derive(Debug)]
+pub struct PageFailure {
+    pub title: String,
+    pub source_path: PathBuf,
+    /// Root cause, e.g. unidoc's exit code and stderr
+    pub error: String,
+}
+
+impl PageFailure {
+    pub fn new(page: &crate::book::PageInfo, error: &anyhow::Error) -> Self {
+        Self {
+            title: page.title.clone(),
+            source_path: page.source_path.clone(),
+            error: error.root_cause().to_string(),
+        }
+    }
+}
+
+/// Returned by `Builder::build` when some pages failed under `--keep-going`
+#[derive(Debug)]
+pub struct BuildFailures(pub Vec<PageFailure>);
+
+impl BuildFailures {
+    /// Failures grouped by identical error, each followed by the affected pages
+    pub fn summary(&self) -> String {
+        let mut groups: Vec<(&str, Vec<&PageFailure>)> = Vec::new();
+        for failure in &self.0 {
+            match groups.iter_mut().find(|(error, _)| *error == failure.error) {
+                Some((_, pages)) => pages.push(failure),
+                None => groups.push((&failure.error, vec![failure])),
+            }
+        }
+
+        let mut text = format!("{} page(s) failed to build:\n", self.0.len());
+        for (error, pages) in groups {
+            text.push('\n');
+            for line in error.lines() {
+                text.push_str(&format!("  {}\n", line));
+            }
+            for page in pages {
+                text.push_str(&format!(
+                    "    - {} ({})\n",
+                    page.source_path.display(),
+                    page.title
+                ));
+            }
+        }
+        text
+    }
+}
+
+impl std::fmt::Display for BuildFailures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} page(s) failed to build", self.0.len())
+    }
+}
+
+impl std::error::Error for BuildFailures {}
+
+/// 1536 -> "1.5 KB"
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(name: &str, unidoc_ms: u64, output_bytes: u64) -> PageReport {
+        PageReport {
+            title: name.to_string(),
+            source_path: PathBuf::from(format!("src/{}.md", name)),
+            output_filename: format!("{}.html", name),
+            unidoc_ms,
+            output_bytes,
+            inlined_bytes: 100,
+            toc_bytes: 20,
+            word_count: 10,
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_report_totals_and_summary() {
+        let mut pages = vec![page("a", 30, 2048), page("b", 120, 512), page("c", 5, 4096)];
+        pages[1].warnings.push("unknown directive".to_string());
+        let report = BuildReport::new(pages, Duration::from_millis(400), 1500);
+
+        assert_eq!(report.totals.pages, 3);
+        assert_eq!(report.totals.unidoc_ms, 155);
+        assert_eq!(report.totals.output_bytes, 6656);
+        assert_eq!(report.totals.word_count, 30);
+        assert_eq!(report.totals.warnings, 1);
+
+        let summary = report.summary();
+        assert!(summary.starts_with("Build report: 3 pages in 400 ms (unidoc 155 ms), 6.5 KB output, search index 1.5 KB, 1 warning(s)\n"));
+        let slowest = summary.find("b.html").unwrap();
+        assert!(slowest < summary.find("   30 ms  a.html").unwrap());
+        assert!(
+            summary.contains("Largest pages:\n     4.0 KB  c.html (inlined 100 B, TOC 20 B)\n")
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["pages"][1]["warnings"][0], "unknown directive");
+        assert_eq!(json["totals"]["search_index_bytes"], 1500);
+    }
+
+    #[test]
+    fn test_failure_summary() {
+        let failure = |name: &str, error: &str| PageFailure {
+            title: name.to_string(),
+            source_path: PathBuf::from(format!("src/{}.md", name)),
+            error: error.to_string(),
+        };
+        let failures = BuildFailures(vec![
+            failure(
+                "a",
+                "unidoc failed with exit code 1:\nunknown directive @[foo]",
+            ),
+            failure("b", "unidoc failed with exit code 2"),
+            failure(
+                "c",
+                "unidoc failed with exit code 1:\nunknown directive @[foo]",
+            ),
+        ]);
+        assert_eq!(failures.to_string(), "3 page(s) failed to build");
+        assert_eq!(
+            failures.summary(),
+            "3 page(s) failed to build:\n\
+             \n  unidoc failed with exit code 1:\n  unknown directive @[foo]\n    - src/a.md (a)\n    - src/c.md (c)\n\
+             \n  unidoc failed with exit code 2\n    - src/b.md (b)\n"
+        );
+    }
+}
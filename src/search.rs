@@ -1,118 +1,815 @@
-use crate::book::Book;
+use crate::book::{Book, PageInfo};
+use crate::history::History;
+use crate::meta::FrontMatter;
+use crate::search_tokenizer::Normalization;
 use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
 
+/// Manifest loaded on the first search
+const MANIFEST_FILENAME: &str = "search-index.json";
+/// Directory of the shards listed in the manifest
+const SHARD_DIR: &str = "search-index";
+/// Shards are cut once their JSON exceeds this size
+const SHARD_BYTES: usize = 64 * 1024;
+/// Characters of context before / after the first query word in a snippet
+const SNIPPET_BEFORE: usize = 40;
+const SNIPPET_AFTER: usize = 60;
+
+/// Term frequency multiplier for the page title
+const TITLE_BOOST: f32 = 3.0;
+/// Term frequency multiplier for the section (H2) title
+const HEADING_BOOST: f32 = 2.0;
+/// BM25 parameters
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// search-index.json: which shard holds which terms and entries
+#[derive(Debug, Serialize)]
+struct SearchManifest<'a> {
+    normalization: Normalization,
+    /// First term of each `terms-N.json`
+    terms: Vec<&'a str>,
+    /// Index of the first entry of each `entries-N.json`
+    entries: Vec<usize>,
+}
+
+/// Inverted index queried by search.js
 #[derive(Debug, Serialize)]
 pub struct SearchIndex {
-    pages: Vec<SearchEntry>,
+    /// Applied to the terms; queries must be normalized the same way
+    normalization: Normalization,
+    entries: Vec<SearchEntry>,
+    /// Term -> [entry, BM25 weight] pairs, sorted by entry
+    terms: BTreeMap<String, Vec<(u32, f32)>>,
+}
+
+impl SearchIndex {
+    /// Tokenize the entries and precompute a BM25 weight per (term, entry)
+    fn new(entries: Vec<SearchEntry>, normalization: Normalization) -> Self {
+        let mut frequencies: Vec<HashMap<String, f32>> = Vec::with_capacity(entries.len());
+        let mut lengths = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let mut tf: HashMap<String, f32> = HashMap::new();
+            let mut length = 0;
+            let fields = [
+                (entry.title.as_str(), TITLE_BOOST),
+                (entry.section.as_deref().unwrap_or_default(), HEADING_BOOST),
+                (entry.content.as_str(), 1.0),
+                (entry.code.as_str(), 1.0),
+            ];
+            for (text, boost) in fields {
+                for token in normalization.tokenize(text) {
+                    *tf.entry(token).or_default() += boost;
+                    length += 1;
+                }
+            }
+            frequencies.push(tf);
+            lengths.push(length as f32);
+        }
+
+        let count = entries.len() as f32;
+        let average_length = (lengths.iter().sum::<f32>() / count.max(1.0)).max(1.0);
+        let mut document_frequency: HashMap<&str, f32> = HashMap::new();
+        for tf in &frequencies {
+            for term in tf.keys() {
+                *document_frequency.entry(term).or_default() += 1.0;
+            }
+        }
+
+        let mut terms: BTreeMap<String, Vec<(u32, f32)>> = BTreeMap::new();
+        for (index, tf) in frequencies.iter().enumerate() {
+            let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * lengths[index] / average_length);
+            for (term, &frequency) in tf {
+                let df = document_frequency[term.as_str()];
+                let idf = (1.0 + (count - df + 0.5) / (df + 0.5)).ln();
+                let weight = idf * frequency * (BM25_K1 + 1.0) / (frequency + norm);
+                terms
+                    .entry(term.clone())
+                    .or_default()
+                    .push((index as u32, (weight * 1000.0).round() / 1000.0));
+            }
+        }
+
+        Self {
+            normalization,
+            entries,
+            terms,
+        }
+    }
+
+    /// Entries containing every query token (the last one also as a prefix),
+    /// best first, with the summed weights. Same ranking as search.js.
+    pub fn rank(&self, query: &str) -> Vec<(usize, f64)> {
+        let mut seen = HashSet::new();
+        let tokens: Vec<String> = self
+            .normalization
+            .tokenize(query)
+            .into_iter()
+            .filter(|token| seen.insert(token.clone()))
+            .collect();
+
+        let mut scores: Option<HashMap<u32, f64>> = None;
+        for (i, token) in tokens.iter().enumerate() {
+            let weights = self.lookup(token, i + 1 == tokens.len());
+            scores = Some(match scores {
+                None => weights,
+                Some(scores) => weights
+                    .into_iter()
+                    .filter_map(|(entry, weight)| Some((entry, scores.get(&entry)? + weight)))
+                    .collect(),
+            });
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(entry, score)| (entry as usize, score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Entry -> weight of a term, or the best weight of the terms it prefixes
+    fn lookup(&self, token: &str, prefix: bool) -> HashMap<u32, f64> {
+        let mut weights = HashMap::new();
+        let terms = self
+            .terms
+            .range::<str, _>((Bound::Included(token), Bound::Unbounded))
+            .take_while(|(term, _)| {
+                if prefix {
+                    term.starts_with(token)
+                } else {
+                    *term == token
+                }
+            });
+        for (_, postings) in terms {
+            for &(entry, weight) in postings {
+                // The value search.js parses from the JSON, so that sums match
+                let weight = (f64::from(weight) * 1000.0).round() / 1000.0;
+                let best = weights.entry(entry).or_insert(weight);
+                *best = best.max(weight);
+            }
+        }
+        weights
+    }
+
+    /// Ranked matches for `unibook search`
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchMatch> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.rank(query)
+            .into_iter()
+            .take(limit)
+            .map(|(index, score)| {
+                let entry = &self.entries[index];
+                // Snippet from the code blocks when only they contain a query word
+                let text =
+                    if !contains_any(&entry.content, &words) && contains_any(&entry.code, &words) {
+                        &entry.code
+                    } else {
+                        &entry.content
+                    };
+                let (snippet, highlights) = snippet(text, &words);
+                SearchMatch {
+                    title: entry.title.clone(),
+                    section: entry.section.clone(),
+                    url: entry.url.clone(),
+                    source_path: entry.source_path.clone(),
+                    line: entry.line,
+                    score,
+                    snippet,
+                    highlights,
+                }
+            })
+            .collect()
+    }
+
+    /// Split the terms into `{term: postings}` objects of about `max_bytes`,
+    /// as (first term, JSON) pairs
+    fn term_shards(&self, max_bytes: usize) -> Result<Vec<(&str, String)>> {
+        let mut shards: Vec<(&str, String)> = Vec::new();
+        for (term, postings) in &self.terms {
+            let member = format!(
+                "{}:{}",
+                serde_json::to_string(term)?,
+                serde_json::to_string(postings)?
+            );
+            match shards.last_mut() {
+                Some((_, json)) if json.len() + member.len() <= max_bytes => {
+                    json.push(',');
+                    json.push_str(&member);
+                }
+                _ => shards.push((term, format!("{{{}", member))),
+            }
+        }
+        for (_, json) in &mut shards {
+            json.push('}');
+        }
+        Ok(shards)
+    }
+
+    /// Split the entries into arrays of about `max_bytes`, as
+    /// (first entry, JSON) pairs. The sections of a page stay in one shard.
+    fn entry_shards(&self, max_bytes: usize) -> Result<Vec<(usize, String)>> {
+        let mut shards: Vec<(usize, String)> = Vec::new();
+        let mut start = 0;
+        while start < self.entries.len() {
+            let page = self.entries[start].page_url();
+            let end = self.entries[start..]
+                .iter()
+                .position(|entry| entry.page_url() != page)
+                .map_or(self.entries.len(), |len| start + len);
+            let group = self.entries[start..end]
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<serde_json::Result<Vec<_>>>()?
+                .join(",");
+            match shards.last_mut() {
+                Some((_, json)) if json.len() + group.len() <= max_bytes => {
+                    json.push(',');
+                    json.push_str(&group);
+                }
+                _ => shards.push((start, format!("[{}", group))),
+            }
+            start = end;
+        }
+        for (_, json) in &mut shards {
+            json.push(']');
+        }
+        Ok(shards)
+    }
+
+    /// Replace every entry URL (e.g. to point into a single-file export)
+    pub fn map_urls(&mut self, f: impl Fn(&str) -> String) {
+        for entry in &mut self.entries {
+            entry.url = f(&entry.url);
+        }
+    }
 }
 
+/// One H2 section of a page (or the text before the first H2)
 #[derive(Debug, Serialize)]
 pub struct SearchEntry {
+    /// Page title
     title: String,
+    /// H2 title; None for the text before the first H2
+    section: Option<String>,
+    /// base_path-absolute page URL, with `#id` of the section (same ids as `PageInfo.sections`)
     url: String,
     content: String,
+    /// Text of the code blocks in the section
+    code: String,
+    last_updated: Option<String>,
+    contributors: Vec<String>,
+    /// Source of the section, for `unibook search`: the page, or the
+    /// included file the H2 comes from
+    #[serde(skip)]
+    source_path: PathBuf,
+    /// Line of the H2 in `source_path`
+    #[serde(skip)]
+    line: usize,
+}
+
+/// A result of `unibook search`
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    pub title: String,
+    pub section: Option<String>,
+    pub url: String,
+    pub source_path: PathBuf,
+    pub line: usize,
+    pub score: f64,
+    /// Text around the first query word found
+    pub snippet: String,
+    /// Byte ranges of the query words in the snippet
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl SearchEntry {
+    fn page_url(&self) -> &str {
+        self.url.split('#').next().unwrap_or_default()
+    }
 }
 
 pub struct SearchIndexGenerator;
 
 impl SearchIndexGenerator {
-    pub fn generate(book: &Book, output_dir: &Path) -> Result<()> {
-        let mut entries = Vec::new();
-        Self::collect_entries(&book.items, &mut entries)?;
+    /// Write the manifest and the shards, each with a `.gz` variant
+    pub fn generate(book: &Book, history: &History, output_dir: &Path) -> Result<()> {
+        let index = Self::build_index(book, history)?;
+        let term_shards = index
+            .term_shards(SHARD_BYTES)
+            .context("Failed to serialize search index")?;
+        let entry_shards = index
+            .entry_shards(SHARD_BYTES)
+            .context("Failed to serialize search index")?;
 
-        let index = SearchIndex { pages: entries };
-        let json =
-            serde_json::to_string_pretty(&index).context("Failed to serialize search index")?;
+        // Shards of a previous build may outnumber the new ones
+        let shard_dir = output_dir.join(SHARD_DIR);
+        fs::remove_dir_all(&shard_dir).ok();
+        fs::create_dir_all(&shard_dir).context("Failed to create search index directory")?;
+
+        for (i, (_, json)) in term_shards.iter().enumerate() {
+            write_with_gzip(&shard_dir.join(format!("terms-{}.json", i)), json)?;
+        }
+        for (i, (_, json)) in entry_shards.iter().enumerate() {
+            write_with_gzip(&shard_dir.join(format!("entries-{}.json", i)), json)?;
+        }
 
-        let index_path = output_dir.join("search-index.json");
-        fs::write(&index_path, json).context("Failed to write search index")?;
+        let manifest = SearchManifest {
+            normalization: index.normalization,
+            terms: term_shards.iter().map(|(term, _)| *term).collect(),
+            entries: entry_shards.iter().map(|(entry, _)| *entry).collect(),
+        };
+        let json = serde_json::to_string(&manifest).context("Failed to serialize search index")?;
+        write_with_gzip(&output_dir.join(MANIFEST_FILENAME), &json)?;
 
         Ok(())
     }
 
+    /// `<meta>` tag telling search.js where the manifest is, so that it
+    /// loads from pages in subdirectories too
+    pub fn manifest_meta(book: &Book) -> String {
+        format!(
+            r#"<meta name="unibook-search-index" content="{}/{}">"#,
+            crate::toc::normalize_base_path(&book.config.build.base_path),
+            MANIFEST_FILENAME
+        )
+    }
+
+    /// base_path-absolute URL of a page, with the section id
+    fn entry_url(page: &PageInfo, section: Option<&str>) -> String {
+        match section {
+            Some(title) => format!("{}#{}", page.url, crate::book::section_id(title)),
+            None => page.url.clone(),
+        }
+    }
+
+    /// Total size of the manifest and the shards, without the `.gz` variants
+    pub fn output_bytes(output_dir: &Path) -> u64 {
+        let shards = fs::read_dir(output_dir.join(SHARD_DIR))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len());
+        fs::metadata(output_dir.join(MANIFEST_FILENAME))
+            .map(|metadata| metadata.len())
+            .into_iter()
+            .chain(shards)
+            .sum()
+    }
+
+    pub fn build_index(book: &Book, history: &History) -> Result<SearchIndex> {
+        let mut entries = Vec::new();
+        Self::collect_entries(&book.items, history, &mut entries)?;
+        let normalization = Normalization {
+            width: book.config.search.normalize_width,
+            kana: book.config.search.normalize_kana,
+        };
+        Ok(SearchIndex::new(entries, normalization))
+    }
+
     fn collect_entries(
         items: &[crate::book::BookItem],
+        history: &History,
         entries: &mut Vec<SearchEntry>,
     ) -> Result<()> {
         for item in items {
             match item {
                 crate::book::BookItem::Part { children, .. } => {
                     // Recursively collect from children
-                    Self::collect_entries(children, entries)?;
+                    Self::collect_entries(children, history, entries)?;
                 }
                 crate::book::BookItem::Page(page) => {
-                    let content = Self::extract_text_from_markdown(&page.source_path)?;
-                    entries.push(SearchEntry {
-                        title: page.title.clone(),
-                        url: page.output_filename.clone(),
+                    let (markdown, origins) =
+                        crate::llms::LlmsGenerator::page_markdown_with_origins(page)?;
+                    let page_history = history.page(&page.source_path);
+                    for SectionText {
+                        section,
+                        line,
                         content,
-                    });
+                        code,
+                    } in Self::split_sections(&markdown)
+                    {
+                        if section.is_none() && content.is_empty() && code.is_empty() {
+                            continue;
+                        }
+                        // Included text is reported in the included file
+                        let (source_path, line) = origins
+                            .get(line - 1)
+                            .cloned()
+                            .unwrap_or_else(|| (page.source_path.clone(), line));
+                        entries.push(SearchEntry {
+                            title: page.title.clone(),
+                            url: Self::entry_url(page, section.as_deref()),
+                            section,
+                            content,
+                            code,
+                            last_updated: page_history.last_updated.clone(),
+                            contributors: page_history.contributors.clone(),
+                            source_path,
+                            line,
+                        });
+                    }
                 }
             }
         }
         Ok(())
     }
 
-    fn extract_text_from_markdown(path: &Path) -> Result<String> {
-        let content = fs::read_to_string(path).context("Failed to read markdown file")?;
-
-        // Simple text extraction: remove markdown syntax
-        let text = Self::strip_markdown(&content);
+    /// Split a page at its H2 headings into plain text: first the text before
+    /// the first H2 (no title), then one section per H2
+    fn split_sections(source: &str) -> Vec<SectionText> {
+        let (_, markdown) = FrontMatter::parse(source);
+        let body_start = source.len() - markdown.len();
+        let line_at = |offset: usize| source[..body_start + offset].matches('\n').count() + 1;
 
-        // Normalize whitespace
-        let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
-
-        Ok(normalized)
-    }
-
-    fn strip_markdown(text: &str) -> String {
-        let mut result = String::new();
+        let mut sections = vec![SectionText {
+            line: line_at(0),
+            ..Default::default()
+        }];
         let mut in_code_block = false;
+        // Raw source line of the H2 being read: its title gives the same id as PageInfo.sections
+        let mut heading: Option<(String, String, usize)> = None;
 
-        for line in text.lines() {
-            // Toggle code block state
-            if line.trim_start().starts_with("```") {
-                in_code_block = !in_code_block;
+        for (event, range) in
+            Parser::new_ext(markdown, crate::book::markdown_options()).into_offset_iter()
+        {
+            if let Some((_, text, _)) = &mut heading {
+                match event {
+                    Event::End(TagEnd::Heading(_)) => {
+                        let (raw, text, line) = heading.take().unwrap();
+                        sections.push(SectionText {
+                            section: Some(crate::book::h2_title(&raw, &text)),
+                            line,
+                            ..Default::default()
+                        });
+                    }
+                    Event::Text(t) | Event::Code(t) => text.push_str(&t),
+                    _ => {}
+                }
                 continue;
             }
 
-            // Include code blocks in search
-            if in_code_block {
-                result.push_str(line);
-                result.push(' ');
-                continue;
+            let section = sections.last_mut().unwrap();
+            match event {
+                Event::Start(Tag::Heading {
+                    level: HeadingLevel::H2,
+                    ..
+                }) => {
+                    let line = line_at(range.start);
+                    let raw = markdown[range].lines().next().unwrap_or_default();
+                    heading = Some((raw.to_string(), String::new(), line));
+                }
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Text(t) if in_code_block => {
+                    section.code.push_str(&t);
+                    section.code.push(' ');
+                }
+                // Link text and image alt text arrive as Text; URLs are dropped
+                Event::Text(t) | Event::Code(t) => section.content.push_str(&t),
+                Event::Html(html) | Event::InlineHtml(html) => {
+                    section.content.push_str(&strip_tags(&html));
+                }
+                Event::SoftBreak | Event::HardBreak | Event::End(_) => section.content.push(' '),
+                // Math, footnote references, task list markers, rules
+                _ => {}
             }
+        }
+
+        for section in &mut sections {
+            section.content = normalize_whitespace(&section.content);
+            section.code = normalize_whitespace(&section.code);
+        }
+        sections
+    }
+}
+
+/// Plain text of one section of a page
+#[derive(Debug, Default)]
+struct SectionText {
+    /// H2 title; None for the text before the first H2
+    section: Option<String>,
+    /// 1-based line where the section starts
+    line: usize,
+    content: String,
+    /// Text of fenced and indented code blocks
+    code: String,
+}
 
-            // Remove common markdown syntax
-            let cleaned = line
-                .trim_start_matches('#') // Headers
-                .trim_start_matches('-') // List items
-                .trim_start_matches('*') // List items
-                .trim_start_matches('>') // Blockquotes
-                .replace("**", "") // Bold
-                .replace("__", "") // Bold
-                .replace(['*', '_', '`'], ""); // Italic and inline code
+fn contains_any(text: &str, words: &[String]) -> bool {
+    let text = text.to_lowercase();
+    words.iter().any(|word| text.contains(word.as_str()))
+}
+
+/// Text around the first of `words` (lowercase) found in `text`, and the
+/// byte ranges of all their occurrences in it (same window as search.js)
+fn snippet(text: &str, words: &[String]) -> (String, Vec<(usize, usize)>) {
+    let chars: Vec<char> = text.chars().collect();
+    // One lowercase char per char, so that indices line up
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let words: Vec<Vec<char>> = words
+        .iter()
+        .map(|word| word.chars().collect())
+        .filter(|word: &Vec<char>| !word.is_empty())
+        .collect();
+    let find = |from: usize| {
+        (from..lower.len()).find_map(|i| {
+            words
+                .iter()
+                .find(|word| lower[i..].starts_with(word))
+                .map(|word| (i, word.len()))
+        })
+    };
 
-            result.push_str(&cleaned);
-            result.push(' ');
+    let Some((index, length)) = find(0) else {
+        let start: String = chars.iter().take(100).collect();
+        return (format!("{}...", start), Vec::new());
+    };
+    let start = index.saturating_sub(SNIPPET_BEFORE);
+    let end = chars.len().min(index + length + SNIPPET_AFTER);
+
+    let mut snippet = String::new();
+    let mut highlights = Vec::new();
+    if start > 0 {
+        snippet.push_str("...");
+    }
+    let mut i = start;
+    while i < end {
+        match find(i).filter(|(found, length)| *found == i && found + length <= end) {
+            Some((_, length)) => {
+                let from = snippet.len();
+                snippet.extend(&chars[i..i + length]);
+                highlights.push((from, snippet.len()));
+                i += length;
+            }
+            None => {
+                snippet.push(chars[i]);
+                i += 1;
+            }
         }
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    (snippet, highlights)
+}
+
+fn write_with_gzip(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).context(format!("Failed to write {}", path.display()))?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(content.as_bytes())
+        .and_then(|_| encoder.finish())
+        .and_then(|gz| fs::write(path.with_extension("json.gz"), gz))
+        .context(format!("Failed to write {}.gz", path.display()))
+}
 
-        result
+/// Text outside `<...>` tags
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
     }
+    text
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn entry(title: &str, section: Option<&str>, content: &str) -> SearchEntry {
+        SearchEntry {
+            title: title.to_string(),
+            section: section.map(|s| s.to_string()),
+            url: String::new(),
+            content: content.to_string(),
+            code: String::new(),
+            last_updated: None,
+            contributors: vec![],
+            source_path: PathBuf::new(),
+            line: 1,
+        }
+    }
+
+    #[test]
+    fn test_bm25_weights() {
+        let index = SearchIndex::new(
+            vec![
+                entry("Install", None, "Steps"),
+                entry("Guide", Some("Install"), "Steps"),
+                entry(
+                    "Guide",
+                    Some("Usage"),
+                    "Run install after setup, then install plugins",
+                ),
+                entry("検索", None, "全文検索の使い方"),
+            ],
+            Normalization::default(),
+        );
+        let weight = |term: &str, entry: u32| {
+            index.terms[term]
+                .iter()
+                .find(|(e, _)| *e == entry)
+                .map(|(_, w)| *w)
+        };
+        // Title beats heading beats body, even with two body occurrences
+        let title = weight("install", 0).unwrap();
+        let heading = weight("install", 1).unwrap();
+        let body = weight("install", 2).unwrap();
+        assert!(
+            title > heading && heading > body,
+            "{title} {heading} {body}"
+        );
+        assert_eq!(weight("install", 3), None);
+        // Rare terms weigh more than common ones
+        assert!(weight("plugins", 2).unwrap() > body);
+        // CJK bigrams from the title and body
+        assert!(weight("検索", 3).unwrap() > weight("全文", 3).unwrap());
+        assert!(index.terms.contains_key("使い"));
+    }
+
+    #[test]
+    fn test_search() {
+        let index = SearchIndex::new(
+            vec![
+                entry("Setup", None, "Install the tool, then configure it"),
+                entry(
+                    "Guide",
+                    Some("Installing plugins"),
+                    "Plugins extend the tool",
+                ),
+                entry("Guide", Some("Usage"), "Run it"),
+            ],
+            Normalization::default(),
+        );
+        // Only the last word matches as a prefix, every word must match
+        assert_eq!(
+            index
+                .rank("INSTALL tool")
+                .iter()
+                .map(|(e, _)| *e)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+        assert_eq!(index.rank("tool inst").len(), 2);
+        assert!(index.rank("tool usage").is_empty());
+        assert!(index.rank("  ").is_empty());
+
+        let found = index.search("plugins tool", 10);
+        assert_eq!(found[0].section.as_deref(), Some("Installing plugins"));
+        let snippet = &found[0].snippet;
+        let highlighted: Vec<&str> = found[0]
+            .highlights
+            .iter()
+            .map(|&(start, end)| &snippet[start..end])
+            .collect();
+        assert_eq!(snippet, "Plugins extend the tool");
+        assert_eq!(highlighted, vec!["Plugins", "tool"]);
+    }
+
+    #[test]
+    fn test_snippet() {
+        let text = format!("{}検索 here{}", "あ".repeat(50), "い".repeat(70));
+        let (snippet, highlights) = super::snippet(&text, &["検索".to_string()]);
+        assert_eq!(
+            snippet,
+            format!("...{}検索 here{}...", "あ".repeat(40), "い".repeat(55))
+        );
+        assert_eq!(&snippet[highlights[0].0..highlights[0].1], "検索");
+
+        let (snippet, highlights) = super::snippet("no match", &["x".to_string()]);
+        assert_eq!(snippet, "no match...");
+        assert!(highlights.is_empty());
+    }
+
+    #[test]
+    fn test_entry_url() {
+        let page = PageInfo {
+            title: "B".to_string(),
+            source_path: "src/a/b.md".into(),
+            output_filename: "a/b.html".to_string(),
+            url: "/book/a/b.html".to_string(),
+            sections: vec![],
+            word_count: 0,
+        };
+        assert_eq!(
+            SearchIndexGenerator::entry_url(&page, None),
+            "/book/a/b.html"
+        );
+        assert_eq!(
+            SearchIndexGenerator::entry_url(&page, Some("Getting Started")),
+            format!(
+                "/book/a/b.html#{}",
+                crate::book::section_id("Getting Started")
+            )
+        );
+    }
+
+    #[test]
+    fn test_shards() {
+        let mut entries = vec![];
+        for page in 0..6 {
+            for section in ["Intro", "Usage"] {
+                let mut e = entry(&format!("Page {page}"), Some(section), "alpha beta gamma");
+                e.url = format!("p{page}.html#{section}");
+                entries.push(e);
+            }
+        }
+        let index = SearchIndex::new(entries, Normalization::default());
+
+        let terms = index.term_shards(40).unwrap();
+        assert!(terms.len() > 1);
+        let mut merged = BTreeMap::new();
+        for (first, json) in &terms {
+            let shard: BTreeMap<String, Vec<(u32, f32)>> = serde_json::from_str(json).unwrap();
+            assert_eq!(shard.keys().next().unwrap(), first);
+            merged.extend(shard);
+        }
+        assert_eq!(merged, index.terms);
+
+        // Cut only between pages
+        let shards = index.entry_shards(500).unwrap();
+        assert!(shards.len() > 1);
+        let starts: Vec<usize> = shards.iter().map(|(start, _)| *start).collect();
+        assert!(starts.iter().all(|start| start % 2 == 0), "{starts:?}");
+        let total: usize = shards
+            .iter()
+            .map(|(_, json)| {
+                serde_json::from_str::<Vec<serde_json::Value>>(json)
+                    .unwrap()
+                    .len()
+            })
+            .sum();
+        assert_eq!(total, 12);
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let markdown = "# Guide\nIntro text\n## Setup\nInstall it\n```sh\n## not a heading\n```\nUsage\n---\nRun it\n";
+        let sections = SearchIndexGenerator::split_sections(markdown);
+        let titles: Vec<Option<&str>> = sections.iter().map(|s| s.section.as_deref()).collect();
+        // Setext H2s are sections too, with the same title as in PageInfo.sections
+        assert_eq!(titles, vec![None, Some("Setup"), Some("Usage")]);
+        assert_eq!(sections[0].content, "Guide Intro text");
+        assert_eq!(sections[1].content, "Install it");
+        assert_eq!(sections[1].code, "## not a heading");
+        assert_eq!(sections[2].content, "Run it");
+        let lines: Vec<usize> = sections.iter().map(|s| s.line).collect();
+        assert_eq!(lines, vec![1, 3, 8]);
+    }
+
     #[test]
-    fn test_strip_markdown() {
-        let input = "# Header\n**bold** *italic*";
-        let output = SearchIndexGenerator::strip_markdown(input);
-        assert!(!output.contains('#'));
-        assert!(!output.contains('*'));
+    fn test_extract_text() {
+        let markdown = concat!(
+            "---\ntitle: Hidden\n---\n",
+            "# Header\n",
+            "**bold** *italic* `snake_case_name` and [link text](https://example.com/url)\n\n",
+            "![diagram alt](img/flow.png \"Flow\")\n\n",
+            "| Name | Value |\n|------|-------|\n| a | 1 |\n\n",
+            "<div class=\"note\">Note <b>this</b></div>\n\n",
+            "Euler $e^{i\\pi}$ identity\n\n",
+            "$$\nx^2\n$$\n",
+            "## Code `x`\n",
+            "```rust\nfn main() {}\n```\n",
+        );
+        let sections = SearchIndexGenerator::split_sections(markdown);
+        assert_eq!(
+            sections[0].content,
+            "Header bold italic snake_case_name and link text diagram alt Name Value a 1 Note this Euler identity"
+        );
+        assert!(sections[0].code.is_empty());
+        // Same title (and id) as PageInfo.sections
+        assert_eq!(sections[1].section.as_deref(), Some("Code `x`"));
+        assert_eq!(sections[1].code, "fn main() {}");
+        // Lines count the front matter
+        assert_eq!((sections[0].line, sections[1].line), (4, 20));
     }
 }
@@ -60,4 +60,24 @@ impl SearchAssets {
     pub fn prism_retry_js() -> &'static str {
         include_str!("../assets/prism-retry.js")
     }
+
+    pub fn not_found_js() -> &'static str {
+        include_str!("../assets/not-found.js")
+    }
+
+    pub fn print_css() -> &'static str {
+        include_str!("../assets/print.css")
+    }
+
+    pub fn epub_css() -> &'static str {
+        include_str!("../assets/epub.css")
+    }
+
+    pub fn copy_markdown_html() -> &'static str {
+        include_str!("../assets/copy-markdown.html")
+    }
+
+    pub fn copy_markdown_js() -> &'static str {
+        include_str!("../assets/copy-markdown.js")
+    }
 }
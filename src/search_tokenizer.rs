@@ -0,0 +1,169 @@
+use crate::book::is_cjk;
+use serde::{Deserialize, Serialize};
+
+/// Half-width katakana U+FF66..=U+FF9D, in order
+const HALF_WIDTH_KATAKANA: &str = "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+/// Text normalization applied to both the index and queries
+/// (mirrored by `normalize` in search.js)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Normalization {
+    /// Full-width ASCII -> ASCII, half-width katakana -> full-width
+    pub width: bool,
+    /// Katakana -> hiragana
+    pub kana: bool,
+}
+
+impl Normalization {
+    pub fn normalize(&self, text: &str) -> String {
+        let mut folded: Vec<char> = Vec::with_capacity(text.len());
+        for c in text.chars() {
+            let c = if self.width { fold_width(c) } else { c };
+            // Half-width (semi-)voiced sound marks combine with the previous kana
+            if self.width && (c == '\u{FF9E}' || c == '\u{FF9F}') {
+                if let Some(voiced) = folded.last().and_then(|&last| voice(last, c)) {
+                    folded.pop();
+                    folded.push(voiced);
+                }
+                continue;
+            }
+            folded.push(c);
+        }
+        folded
+            .into_iter()
+            .map(|c| {
+                if self.kana {
+                    katakana_to_hiragana(c)
+                } else {
+                    c
+                }
+            })
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Normalized tokens: Latin words, and character bigrams of CJK runs
+    /// (a single CJK character on its own is kept as a unigram)
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        let text = self.normalize(text);
+        let mut tokens = Vec::new();
+        let mut word = String::new();
+        let mut cjk: Vec<char> = Vec::new();
+
+        for c in text.chars().chain(std::iter::once(' ')) {
+            if is_cjk(c) {
+                push_word(&mut tokens, &mut word);
+                cjk.push(c);
+            } else {
+                push_bigrams(&mut tokens, &mut cjk);
+                if c.is_alphanumeric() || c == '_' {
+                    word.push(c);
+                } else {
+                    push_word(&mut tokens, &mut word);
+                }
+            }
+        }
+        tokens
+    }
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            width: true,
+            kana: false,
+        }
+    }
+}
+
+fn push_word(tokens: &mut Vec<String>, word: &mut String) {
+    if !word.is_empty() {
+        tokens.push(std::mem::take(word));
+    }
+}
+
+fn push_bigrams(tokens: &mut Vec<String>, run: &mut Vec<char>) {
+    match run.len() {
+        0 => {}
+        1 => tokens.push(run[0].to_string()),
+        _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>())),
+    }
+    run.clear();
+}
+
+fn fold_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        '\u{FF66}'..='\u{FF9D}' => HALF_WIDTH_KATAKANA
+            .chars()
+            .nth((c as u32 - 0xFF66) as usize)
+            .unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// カ + ﾞ -> ガ, ハ + ﾟ -> パ
+fn voice(base: char, mark: char) -> Option<char> {
+    let offset = if mark == '\u{FF9E}' { 1 } else { 2 };
+    match base {
+        'ウ' if offset == 1 => Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト'
+            if offset == 1 =>
+        {
+            char::from_u32(base as u32 + 1)
+        }
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(base as u32 + offset),
+        _ => None,
+    }
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let width = Normalization::default();
+        assert_eq!(width.normalize("ＵｎｉＢｏｏｋ　２"), "unibook 2");
+        assert_eq!(width.normalize("ｶﾞｲﾄﾞ ﾊﾟｽ"), "ガイド パス");
+        assert_eq!(width.normalize("カタカナ"), "カタカナ");
+
+        let kana = Normalization {
+            width: true,
+            kana: true,
+        };
+        assert_eq!(kana.normalize("ｶﾞｲﾄﾞとカタカナー"), "がいどとかたかなー");
+
+        let none = Normalization {
+            width: false,
+            kana: false,
+        };
+        assert_eq!(none.normalize("ＡＢ"), "ａｂ");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let n = Normalization::default();
+        assert_eq!(
+            n.tokenize("Install snake_case_name v2.0"),
+            vec!["install", "snake_case_name", "v2", "0"]
+        );
+        assert_eq!(
+            n.tokenize("全文検索をBM25で"),
+            vec!["全文", "文検", "検索", "索を", "bm25", "で"]
+        );
+        assert_eq!(n.tokenize("本 と"), vec!["本", "と"]);
+        // Same CJK ranges as word counts, including the supplementary planes
+        assert_eq!(n.tokenize("𠮷野家々"), vec!["𠮷野", "野家", "家々"]);
+        assert!(n.tokenize(" -- ").is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::book::{Book, PageInfo};
+use crate::html::{base64_encode, is_external, media_type, rewrite_attribute};
+use crate::search::SearchIndex;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+pub struct SingleHtml;
+
+impl SingleHtml {
+    /// Replace local image sources with data URIs
+    pub fn embed_images(book: &Book, base_dir: &Path, page: &PageInfo, html: &str) -> String {
+        rewrite_attribute(html, "src", |src| {
+            if is_external(src) {
+                return src.to_string();
+            }
+            let Some(media_type) = media_type(src) else {
+                return src.to_string();
+            };
+            match book
+                .find_asset(base_dir, page, src)
+                .and_then(|path| std::fs::read(path).ok())
+            {
+                Some(data) => format!("data:{};base64,{}", media_type, base64_encode(&data)),
+                None => {
+                    crate::log::warning!(
+                        "image not found for single HTML: {} (in {})",
+                        src,
+                        page.output_filename
+                    );
+                    src.to_string()
+                }
+            }
+        })
+    }
+
+    /// Script embedding the search index, so that search works without fetching
+    pub fn search_script(index: &SearchIndex) -> Result<String> {
+        let json = serde_json::to_string(index).context("Failed to serialize search index")?;
+        Ok(format!(
+            "<script>window.UNIBOOK_SEARCH_INDEX = {};</script>\n",
+            json.replace("</", "<\\/")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_embed_images() {
+        let temp_dir = std::env::temp_dir().join("unibook-test-single-html");
+        let src_dir = temp_dir.join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(src_dir.join("dot.png"), b"foo").unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+[book]
+title = "Test"
+
+[[pages]]
+title = "Page"
+path = "page.md"
+"#,
+        )
+        .unwrap();
+        let book = Book {
+            config,
+            items: vec![],
+        };
+        let page = PageInfo {
+            title: "Page".to_string(),
+            source_path: src_dir.join("page.md"),
+            output_filename: "page.html".to_string(),
+            url: "/page.html".to_string(),
+            sections: vec![],
+            word_count: 0,
+        };
+
+        let html = r#"<img src="dot.png"><img src="missing.png"><img src="https://x/y.png">"#;
+        let out = SingleHtml::embed_images(&book, &temp_dir, &page, html);
+
+        assert!(out.contains(r#"src="data:image/png;base64,Zm9v""#));
+        assert!(out.contains(r#"src="missing.png""#));
+        assert!(out.contains(r#"src="https://x/y.png""#));
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+}
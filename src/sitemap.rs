@@ -0,0 +1,152 @@
+use crate::book::{Book, BookItem};
+use crate::history::History;
+use crate::log;
+use crate::toc::attr_escape;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+pub struct SitemapGenerator;
+
+impl SitemapGenerator {
+    /// Generate sitemap.xml and robots.txt, returning the files written
+    /// Does nothing when site_url is not configured
+    pub fn generate(
+        book: &Book,
+        history: &History,
+        output_dir: &Path,
+    ) -> Result<Vec<&'static str>> {
+        let Some(sitemap_url) = book.absolute_url("sitemap.xml") else {
+            return Ok(Vec::new());
+        };
+
+        let mut entries = Vec::new();
+        Self::collect_entries(book, history, &book.items, &mut entries);
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+        for (loc, lastmod) in &entries {
+            xml.push_str("  <url>\n");
+            xml.push_str(&format!("    <loc>{}</loc>\n", attr_escape(loc)));
+            if let Some(lastmod) = lastmod {
+                xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod));
+            }
+            xml.push_str("  </url>\n");
+        }
+        xml.push_str("</urlset>\n");
+
+        fs::write(output_dir.join("sitemap.xml"), xml).context("Failed to write sitemap.xml")?;
+
+        // Crawlers only read robots.txt at the root of the host
+        let base_path = crate::toc::normalize_base_path(&book.config.build.base_path);
+        if !base_path.is_empty() {
+            log::info!(
+                "Skipped robots.txt: it is not read under base_path {} (add \"Sitemap: {}\" to the site's /robots.txt)",
+                base_path,
+                sitemap_url
+            );
+            return Ok(vec!["sitemap.xml"]);
+        }
+        let robots = format!("User-agent: *\nAllow: /\n\nSitemap: {}\n", sitemap_url);
+        fs::write(output_dir.join("robots.txt"), robots).context("Failed to write robots.txt")?;
+
+        Ok(vec!["sitemap.xml", "robots.txt"])
+    }
+
+    fn collect_entries(
+        book: &Book,
+        history: &History,
+        items: &[BookItem],
+        entries: &mut Vec<(String, Option<String>)>,
+    ) {
+        for item in items {
+            match item {
+                BookItem::Part { children, .. } => {
+                    Self::collect_entries(book, history, children, entries);
+                }
+                BookItem::Page(page) => {
+                    if let Some(loc) = book.absolute_url(&page.output_filename) {
+                        entries.push((loc, history.page(&page.source_path).last_updated));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Format UNIX time (seconds) as YYYY-MM-DD in UTC
+pub fn format_date(secs: u64) -> String {
+    // Howard Hinnant's civil_from_days
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Format UNIX time (seconds) as YYYY-MM-DDThh:mm:ssZ in UTC
+pub fn format_datetime(secs: u64) -> String {
+    let time = secs % 86400;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_date(secs),
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(base_path: &str) -> Book {
+        let config = toml::from_str(&format!(
+            "[book]\ntitle = \"Test\"\nsite_url = \"https://example.com\"\n[build]\nbase_path = \"{}\"\n[[pages]]\ntitle = \"Intro\"\npath = \"intro.md\"\n",
+            base_path
+        ))
+        .unwrap();
+        Book {
+            config,
+            items: vec![],
+        }
+    }
+
+    #[test]
+    fn test_robots_txt_only_at_site_root() {
+        let output_dir = std::env::temp_dir().join("unibook-test-sitemap");
+        fs::create_dir_all(&output_dir).unwrap();
+        let history = History::default();
+
+        let written = SitemapGenerator::generate(&book(""), &history, &output_dir).unwrap();
+        assert_eq!(written, vec!["sitemap.xml", "robots.txt"]);
+        let robots = fs::read_to_string(output_dir.join("robots.txt")).unwrap();
+        assert!(robots.ends_with("Sitemap: https://example.com/sitemap.xml\n"));
+
+        fs::remove_file(output_dir.join("robots.txt")).unwrap();
+        let written = SitemapGenerator::generate(&book("/docs"), &history, &output_dir).unwrap();
+        assert_eq!(written, vec!["sitemap.xml"]);
+        assert!(!output_dir.join("robots.txt").exists());
+
+        fs::remove_dir_all(&output_dir).ok();
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951782400), "2000-02-29");
+        assert_eq!(format_date(1766361600), "2025-12-22");
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        assert_eq!(format_datetime(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_datetime(1766365261), "2025-12-22T01:01:01Z");
+    }
+}
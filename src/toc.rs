@@ -16,24 +16,7 @@ impl TocGenerator {
         base_path: String,
         foldlevel: u8,
     ) -> Self {
-        // Normalize base_path:
-        // - Add "/" at the beginning if not present
-        // - Add "/" at the end if not present
-        let normalized_base_path = if base_path.is_empty() {
-            String::new()
-        } else {
-            let mut path = base_path;
-            // Add leading "/" if missing
-            if !path.starts_with('/') {
-                path = format!("/{}", path);
-            }
-            // Add trailing "/" if missing
-            if !path.ends_with('/') {
-                path = format!("{}/", path);
-            }
-            // Remove the trailing "/" for storage (we'll add it in generate_toc_html)
-            path.trim_end_matches('/').to_string()
-        };
+        let normalized_base_path = normalize_base_path(&base_path);
 
         Self {
             book_title,
@@ -282,6 +265,18 @@ body {
   transition: margin-left 0.3s ease;
 }
 
+/* Last updated date and contributors below the content */
+.page-footer {
+  margin-top: 3em;
+  padding-top: 1em;
+  border-top: 1px solid var(--border-color);
+  color: var(--text-secondary);
+  font-size: 0.85em;
+  display: flex;
+  flex-wrap: wrap;
+  gap: 0.5em 2em;
+}
+
 .toc-header h2 {
   margin-top: 0;
   font-size: 1.5em;
@@ -570,6 +565,23 @@ table tbody tr:hover {
     }
 }
 
+/// Normalize base_path so that it can be prefixed to "/page.html"
+/// - Add "/" at the beginning if not present
+/// - Strip "/" at the end
+/// - Empty string stays empty (links start from "/")
+pub fn normalize_base_path(base_path: &str) -> String {
+    if base_path.is_empty() {
+        return String::new();
+    }
+    let mut path = base_path.to_string();
+    // Add leading "/" if missing
+    if !path.starts_with('/') {
+        path = format!("/{}", path);
+    }
+    // Remove the trailing "/" for storage (we'll add it when building links)
+    path.trim_end_matches('/').to_string()
+}
+
 fn convert_math_delimiters(s: &str) -> String {
     let mut result = String::new();
     let mut in_dollar = false;
@@ -605,11 +617,14 @@ fn convert_math_delimiters(s: &str) -> String {
     result
 }
 
-fn html_escape(s: &str) -> String {
+pub fn html_escape(s: &str) -> String {
     // First convert math delimiters, then escape HTML
-    let converted = convert_math_delimiters(s);
-    converted
-        .replace('&', "&amp;")
+    attr_escape(&convert_math_delimiters(s))
+}
+
+/// Escape text for HTML/XML attributes and text nodes (no math conversion)
+pub fn attr_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
@@ -626,19 +641,25 @@ mod tests {
                 title: "Introduction".to_string(),
                 source_path: std::path::PathBuf::from("src/intro.md"),
                 output_filename: "intro.html".to_string(),
+                url: "/intro.html".to_string(),
                 sections: vec![],
+                word_count: 0,
             }),
             BookItem::Page(PageInfo {
                 title: "Chapter 1".to_string(),
                 source_path: std::path::PathBuf::from("src/chapter1.md"),
                 output_filename: "chapter1.html".to_string(),
+                url: "/chapter1.html".to_string(),
                 sections: vec![],
+                word_count: 0,
             }),
             BookItem::Page(PageInfo {
                 title: "Chapter 2".to_string(),
                 source_path: std::path::PathBuf::from("src/chapter2.md"),
                 output_filename: "chapter2.html".to_string(),
+                url: "/chapter2.html".to_string(),
                 sections: vec![],
+                word_count: 0,
             }),
         ]
     }
@@ -672,6 +693,13 @@ mod tests {
         assert_eq!(html_escape("Title $~$ Test"), "Title \\(~\\) Test");
     }
 
+    #[test]
+    fn test_attr_escape() {
+        assert_eq!(attr_escape("a&b<c>\"d'"), "a&amp;b&lt;c&gt;&quot;d&#39;");
+        // No math conversion
+        assert_eq!(attr_escape("$x$"), "$x$");
+    }
+
     #[test]
     fn test_toc_generator_new() {
         let generator = TocGenerator::new(
@@ -736,7 +764,9 @@ mod tests {
             title: "Chapter <1>".to_string(),
             source_path: std::path::PathBuf::from("src/ch1.md"),
             output_filename: "ch1.html".to_string(),
+            url: "/ch1.html".to_string(),
             sections: vec![],
+            word_count: 0,
         })];
         let html = generator.generate_toc_html(&items, None);
 
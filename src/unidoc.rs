@@ -54,7 +54,19 @@ impl UnidocCommand {
         self
     }
 
-    pub fn execute(&self, input: &Path) -> Result<()> {
+    /// Total size of the files included with -H/-B/-A
+    pub fn included_bytes(&self) -> u64 {
+        self.includes_header
+            .iter()
+            .chain(&self.includes_before)
+            .chain(&self.includes_after)
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Run unidoc; returns the lines it printed to stderr (warnings)
+    pub fn execute(&self, input: &Path) -> Result<Vec<String>> {
         let mut cmd = Command::new("unidoc");
 
         if self.standalone {
@@ -83,32 +95,56 @@ impl UnidocCommand {
 
         cmd.arg(input);
 
+        crate::log::trace!("Running {:?}", cmd);
         let output = cmd
             .output()
             .context("Failed to execute unidoc. Is it installed and in PATH?")?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
             let stdout = String::from_utf8_lossy(&output.stdout);
-
-            // Log stderr to help with debugging
-            eprintln!("\n--- unidoc failed ---");
-            eprintln!("Exit code: {:?}", output.status.code());
-            if !stderr.is_empty() {
-                eprintln!("stderr:\n{}", stderr);
-            }
             if !stdout.is_empty() {
-                eprintln!("stdout:\n{}", stdout);
+                crate::log::debug!("unidoc stdout:\n{}", stdout.trim_end());
             }
-            eprintln!("--- end unidoc error ---\n");
 
-            anyhow::bail!("unidoc failed with exit code {:?}", output.status.code());
+            return Err(UnidocError {
+                exit_code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr)
+                    .trim_end()
+                    .to_string(),
+            }
+            .into());
         }
 
+        Ok(String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+}
+
+/// A failed unidoc run, with the stderr it captured
+#[derive(Debug)]
+pub struct UnidocError {
+    pub exit_code: Option<i32>,
+    pub stderr: String,
+}
+
+impl std::fmt::Display for UnidocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.exit_code {
+            Some(code) => write!(f, "unidoc failed with exit code {}", code)?,
+            None => write!(f, "unidoc was terminated by a signal")?,
+        }
+        if !self.stderr.is_empty() {
+            write!(f, ":\n{}", self.stderr)?;
+        }
         Ok(())
     }
 }
 
+impl std::error::Error for UnidocError {}
+
 impl Default for UnidocCommand {
     fn default() -> Self {
         Self::new()